@@ -8,6 +8,8 @@
 //! - `is_operator_of` : Calls [`operatorOf`](https://proposals.concordium.software/CIS/cis-2.html#operatorof)
 //! - `get_balance` : Calls [`balanceOf`](https://proposals.concordium.software/CIS/cis-2.html#balanceof)
 //! - `transfer` : Calls [`transfer`](https://proposals.concordium.software/CIS/cis-2.html#transfer)
+//! - `is_transferable` : Calls `isSoulbound` of CIS2 contracts supporting the
+//!   `CIS2-Soulbound` standard, such as the CIS2-Multi contract
 
 use concordium_cis2::*;
 use concordium_std::*;
@@ -18,6 +20,10 @@ pub const SUPPORTS_ENTRYPOINT_NAME: &str = "supports";
 pub const OPERATOR_OF_ENTRYPOINT_NAME: &str = "operatorOf";
 pub const BALANCE_OF_ENTRYPOINT_NAME: &str = "balanceOf";
 pub const TRANSFER_ENTRYPOINT_NAME: &str = "transfer";
+pub const IS_SOULBOUND_ENTRYPOINT_NAME: &str = "isSoulbound";
+/// The standard identifier of CIS2 contracts with soulbound token types, which
+/// are queried with `isSoulbound`.
+pub const SOULBOUND_STANDARD_IDENTIFIER: &str = "CIS2-Soulbound";
 
 pub struct Cis2Client;
 
@@ -25,9 +31,20 @@ impl Cis2Client {
     pub(crate) fn supports_cis2<S: HasStateApi, T: IsTokenId, A: IsTokenAmount + Copy>(
         host: &mut impl HasHost<State<S, T, A>, StateApiType = S>,
         cis_contract_address: &ContractAddress,
+    ) -> Result<bool, Cis2ClientError> {
+        Cis2Client::supports(host, cis_contract_address, "CIS-2")
+    }
+
+    /// Queries whether the contract supports the given standard itself.
+    fn supports<S: HasStateApi, T: IsTokenId, A: IsTokenAmount + Copy>(
+        host: &mut impl HasHost<State<S, T, A>, StateApiType = S>,
+        cis_contract_address: &ContractAddress,
+        standard_identifier: &str,
     ) -> Result<bool, Cis2ClientError> {
         let params = SupportsQueryParams {
-            queries: vec![StandardIdentifierOwned::new_unchecked("CIS-2".to_string())],
+            queries: vec![StandardIdentifierOwned::new_unchecked(
+                standard_identifier.to_string(),
+            )],
         };
         let parsed_res: SupportsQueryResponse = Cis2Client::invoke_contract_read_only(
             host,
@@ -35,7 +52,7 @@ impl Cis2Client {
             SUPPORTS_ENTRYPOINT_NAME,
             &params,
         )?;
-        let supports_cis2: bool = {
+        let supports: bool = {
             let f = parsed_res
                 .results
                 .first()
@@ -47,7 +64,7 @@ impl Cis2Client {
             }
         };
 
-        Ok(supports_cis2)
+        Ok(supports)
     }
 
     pub(crate) fn is_operator_of<S: HasStateApi, T: IsTokenId, A: IsTokenAmount + Copy>(
@@ -118,7 +135,7 @@ impl Cis2Client {
             to,
         }]);
 
-        Cis2Client::invoke_contract_read_only(
+        Cis2Client::invoke_contract_read_only::<_, (), _, _, _>(
             host,
             &cis_contract_address,
             TRANSFER_ENTRYPOINT_NAME,
@@ -128,6 +145,30 @@ impl Cis2Client {
         Ok(true)
     }
 
+    /// Queries whether the tokens of a token type can be transferred. Only
+    /// contracts supporting the `CIS2-Soulbound` standard, such as the
+    /// CIS2-Multi contract, can have non-transferable token types, which they
+    /// report with `isSoulbound`. The tokens of any other CIS2 contract are
+    /// considered transferable.
+    pub(crate) fn is_transferable<S: HasStateApi, T: IsTokenId, A: IsTokenAmount + Copy>(
+        host: &mut impl HasHost<State<S, T, A>, StateApiType = S>,
+        token_id: T,
+        cis_contract_address: &ContractAddress,
+    ) -> Result<bool, Cis2ClientError> {
+        if !Cis2Client::supports(host, cis_contract_address, SOULBOUND_STANDARD_IDENTIFIER)? {
+            return Ok(true);
+        }
+
+        let is_soulbound: bool = Cis2Client::invoke_contract_read_only(
+            host,
+            cis_contract_address,
+            IS_SOULBOUND_ENTRYPOINT_NAME,
+            &token_id,
+        )?;
+
+        Ok(!is_soulbound)
+    }
+
     fn invoke_contract_read_only<
         S: HasStateApi,
         R: Deserial,
@@ -17,6 +17,7 @@ pub enum MarketplaceError {
     InvalidCommission,
     InvalidTokenQuantity,
     InvalidRoyalty,
+    TokenNotTransferable,
 }
 
 #[derive(Serialize, Debug, PartialEq, Eq, Reject)]
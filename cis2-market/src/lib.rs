@@ -86,6 +86,7 @@ fn add<S: HasStateApi>(
 
     ensure_supports_cis2(host, &params.cis_contract_address)?;
     ensure_is_operator(host, ctx, &params.cis_contract_address)?;
+    ensure_transferable(host, params.token_id, &params.cis_contract_address)?;
     ensure_balance(
         host,
         params.token_id,
@@ -234,6 +235,20 @@ fn ensure_is_operator<S: HasStateApi, T: IsTokenId + Copy, A: IsTokenAmount + Co
     ensure!(is_operator, MarketplaceError::NotOperator);
     Ok(())
 }
+/// Calls the `isSoulbound` function of CIS2 contracts supporting the
+/// `CIS2-Soulbound` standard.
+/// Returns error if the token is soulbound, or if the query fails.
+fn ensure_transferable<S: HasStateApi>(
+    host: &mut impl HasHost<ContractState<S>, StateApiType = S>,
+    token_id: ContractTokenId,
+    cis_contract_address: &ContractAddress,
+) -> ContractResult<()> {
+    let is_transferable = Cis2Client::is_transferable(host, token_id, cis_contract_address)
+        .map_err(MarketplaceError::Cis2ClientError)?;
+    ensure!(is_transferable, MarketplaceError::TokenNotTransferable);
+    Ok(())
+}
+
 /// Calls the [balanceOf](https://proposals.concordium.software/CIS/cis-2.html#balanceof) function of the CIS2 contract.
 /// Returns error if the returned balance < input balance (balance param).
 fn ensure_balance<S: HasStateApi, T: IsTokenId + Copy, A: IsTokenAmount + Ord + Copy>(
@@ -318,13 +333,18 @@ fn calculate_amounts(
     }
 }
 
+// The tests use the test infrastructure of `concordium-std`, which is
+// deprecated in favor of `concordium-smart-contract-testing`.
 #[concordium_cfg_test]
+#[allow(deprecated)]
 mod test {
     use crate::{
         add, calculate_amounts,
         cis2_client::{
-            BALANCE_OF_ENTRYPOINT_NAME, OPERATOR_OF_ENTRYPOINT_NAME, SUPPORTS_ENTRYPOINT_NAME,
+            BALANCE_OF_ENTRYPOINT_NAME, IS_SOULBOUND_ENTRYPOINT_NAME, OPERATOR_OF_ENTRYPOINT_NAME,
+            SUPPORTS_ENTRYPOINT_NAME,
         },
+        errors::MarketplaceError,
         list,
         params::AddParams,
         state::{Commission, State, TokenInfo, TokenListItem, TokenPriceState, TokenRoyaltyState},
@@ -391,7 +411,7 @@ mod test {
             _s: &mut ContractState<TestStateApi>,
         ) -> Result<(bool, OperatorOfQueryResponse), CallContractError<OperatorOfQueryResponse>>
         {
-            Ok((false, OperatorOfQueryResponse { 0: vec![true] }))
+            Ok((false, OperatorOfQueryResponse(vec![true])))
         }
 
         fn mock_balance_of(
@@ -406,6 +426,15 @@ mod test {
             Ok((false, BalanceOfQueryResponse(vec![1.into()])))
         }
 
+        fn mock_is_soulbound(
+            _p: Parameter,
+            _a: Amount,
+            _a2: &mut Amount,
+            _s: &mut ContractState<TestStateApi>,
+        ) -> Result<(bool, bool), CallContractError<bool>> {
+            Ok((false, false))
+        }
+
         TestHost::setup_mock_entrypoint(
             &mut host,
             CIS_CONTRACT_ADDRESS,
@@ -427,6 +456,13 @@ mod test {
             MockFn::new_v1(mock_balance_of),
         );
 
+        TestHost::setup_mock_entrypoint(
+            &mut host,
+            CIS_CONTRACT_ADDRESS,
+            OwnedEntrypointName::new_unchecked(IS_SOULBOUND_ENTRYPOINT_NAME.to_string()),
+            MockFn::new_v1(mock_is_soulbound),
+        );
+
         let res = add(&ctx, &mut host);
 
         claim!(res.is_ok(), "Results in rejection");
@@ -472,6 +508,151 @@ mod test {
         )
     }
 
+    #[concordium_test]
+    fn should_not_add_non_transferable_token() {
+        let mut ctx = TestReceiveContext::default();
+        ctx.set_sender(ADDRESS_0);
+        ctx.set_self_address(MARKET_CONTRACT_ADDRESS);
+
+        let add_params = AddParams {
+            cis_contract_address: CIS_CONTRACT_ADDRESS,
+            price: Amount::from_ccd(1),
+            token_id: ContractTokenId::from(1),
+            royalty: 0,
+            quantity: ContractTokenAmount::from(1),
+        };
+        let parameter_bytes = to_bytes(&add_params);
+        ctx.set_parameter(&parameter_bytes);
+
+        let mut state_builder = TestStateBuilder::new();
+        let state = State::new(&mut state_builder, 250);
+        let mut host = TestHost::new(state, state_builder);
+
+        fn mock_supports(
+            _p: Parameter,
+            _a: Amount,
+            _a2: &mut Amount,
+            _s: &mut ContractState<TestStateApi>,
+        ) -> Result<(bool, SupportsQueryResponse), CallContractError<SupportsQueryResponse>>
+        {
+            Ok((
+                false,
+                SupportsQueryResponse {
+                    results: vec![SupportResult::Support],
+                },
+            ))
+        }
+
+        fn mock_is_operator_of(
+            _p: Parameter,
+            _a: Amount,
+            _a2: &mut Amount,
+            _s: &mut ContractState<TestStateApi>,
+        ) -> Result<(bool, OperatorOfQueryResponse), CallContractError<OperatorOfQueryResponse>>
+        {
+            Ok((false, OperatorOfQueryResponse(vec![true])))
+        }
+
+        fn mock_supports_cis2_only(
+            p: Parameter,
+            _a: Amount,
+            _a2: &mut Amount,
+            _s: &mut ContractState<TestStateApi>,
+        ) -> Result<(bool, SupportsQueryResponse), CallContractError<SupportsQueryResponse>>
+        {
+            let params: SupportsQueryParams =
+                from_bytes(p.as_ref()).expect_report("Valid supports parameter");
+            let results = params
+                .queries
+                .iter()
+                .map(|id| match id.as_standard_identifier() {
+                    id if id == StandardIdentifier::new_unchecked("CIS-2") => {
+                        SupportResult::Support
+                    }
+                    _ => SupportResult::NoSupport,
+                })
+                .collect();
+            Ok((false, SupportsQueryResponse { results }))
+        }
+
+        fn mock_is_soulbound(
+            _p: Parameter,
+            _a: Amount,
+            _a2: &mut Amount,
+            _s: &mut ContractState<TestStateApi>,
+        ) -> Result<(bool, bool), CallContractError<bool>> {
+            Ok((false, true))
+        }
+
+        fn mock_balance_of(
+            _p: Parameter,
+            _a: Amount,
+            _a2: &mut Amount,
+            _s: &mut ContractState<TestStateApi>,
+        ) -> Result<
+            (bool, BalanceOfQueryResponse<ContractTokenAmount>),
+            CallContractError<BalanceOfQueryResponse<ContractTokenAmount>>,
+        > {
+            Ok((false, BalanceOfQueryResponse(vec![1.into()])))
+        }
+
+        TestHost::setup_mock_entrypoint(
+            &mut host,
+            CIS_CONTRACT_ADDRESS,
+            OwnedEntrypointName::new_unchecked(SUPPORTS_ENTRYPOINT_NAME.to_string()),
+            MockFn::new_v1(mock_supports),
+        );
+
+        TestHost::setup_mock_entrypoint(
+            &mut host,
+            CIS_CONTRACT_ADDRESS,
+            OwnedEntrypointName::new_unchecked(OPERATOR_OF_ENTRYPOINT_NAME.to_string()),
+            MockFn::new_v1(mock_is_operator_of),
+        );
+
+        TestHost::setup_mock_entrypoint(
+            &mut host,
+            CIS_CONTRACT_ADDRESS,
+            OwnedEntrypointName::new_unchecked(IS_SOULBOUND_ENTRYPOINT_NAME.to_string()),
+            MockFn::new_v1(mock_is_soulbound),
+        );
+
+        TestHost::setup_mock_entrypoint(
+            &mut host,
+            CIS_CONTRACT_ADDRESS,
+            OwnedEntrypointName::new_unchecked(BALANCE_OF_ENTRYPOINT_NAME.to_string()),
+            MockFn::new_v1(mock_balance_of),
+        );
+
+        // A token type reported as soulbound is not transferable.
+        let res = add(&ctx, &mut host);
+        claim_eq!(
+            res,
+            Err(MarketplaceError::TokenNotTransferable),
+            "Adding a soulbound token should fail"
+        );
+        claim!(
+            host.state().token_prices.iter().count() == 0,
+            "Token should not be added"
+        );
+
+        // The soulbound query is only used with contracts supporting the
+        // `CIS2-Soulbound` standard.
+        TestHost::setup_mock_entrypoint(
+            &mut host,
+            CIS_CONTRACT_ADDRESS,
+            OwnedEntrypointName::new_unchecked(SUPPORTS_ENTRYPOINT_NAME.to_string()),
+            MockFn::new_v1(mock_supports_cis2_only),
+        );
+
+        let res = add(&ctx, &mut host);
+        claim!(res.is_ok(), "Results in rejection");
+        claim!(
+            host.state().token_prices.iter().count() == 1,
+            "Token should be added"
+        );
+    }
+
     #[concordium_test]
     fn should_list_token() {
         let token_quantity_1 = ContractTokenAmount::from(1);
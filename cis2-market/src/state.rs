@@ -63,7 +63,7 @@ pub struct TokenListItem<T: IsTokenId, A: IsTokenAmount> {
     pub quantity: A,
 }
 
-#[derive(Serial, DeserialWithState, StateClone)]
+#[derive(Serial, DeserialWithState)]
 #[concordium(state_parameter = "S")]
 pub(crate) struct State<S, T, A>
 where
@@ -108,7 +108,7 @@ impl<S: HasStateApi, T: IsTokenId + Copy, A: IsTokenAmount + Copy + ops::Sub<Out
             ),
         };
 
-        self.token_prices.insert(
+        let _ = self.token_prices.insert(
             TokenOwnerInfo::from(token_info, owner),
             TokenPriceState { price, quantity },
         );
@@ -120,7 +120,7 @@ impl<S: HasStateApi, T: IsTokenId + Copy, A: IsTokenAmount + Copy + ops::Sub<Out
             Option::Some(price) => price,
         };
 
-        self.token_prices.insert(
+        let _ = self.token_prices.insert(
             token_info.clone(),
             TokenPriceState {
                 quantity: price.quantity - delta,
@@ -7,8 +7,31 @@
 //!
 //! In this example the contract is initialized with no tokens, and tokens can
 //! be minted through a `mint` contract function, which will only succeed for
-//! the contract owner. No functionality to burn token is defined in this
-//! example.
+//! addresses with the `Minter` role. Tokens can be burned through a `burn` contract
+//! function by the owner of the tokens or an operator of the owner.
+//!
+//! The contract is initialized with the name, symbol and optional metadata of
+//! the collection, which can be read with `collectionInfo`.
+//!
+//! Roles are granted and revoked by addresses with the `Admin` role. The admin
+//! given when initializing the contract, or else the account instantiating the
+//! contract, is granted every role. The admin hands over the contract in two
+//! steps: `transferAdmin` proposes a new admin, which takes over the `Admin`
+//! role, and every other role the previous admin still holds, by calling
+//! `acceptAdmin`.
+//!
+//! The metadata URL of a token type is either set explicitly when minting or
+//! updating the token type, or derived from the base URL of the contract. The
+//! base URL is set when initializing the contract or with `setBaseUrl`, and
+//! every occurrence of `{id}` in it is replaced by the token ID encoded in hex.
+//! If the base URL does not contain `{id}`, the token ID is appended instead.
+//!
+//! The derived metadata URLs can be hidden behind a placeholder until a reveal.
+//! An address with the `Admin` role commits to the final base URL with
+//! `commitReveal`, by the hash of the base URL and the provenance hash of the
+//! final metadata set. The commitment is later opened with `reveal`, which
+//! switches to the final base URL, allowing collectors to verify it was not
+//! changed after minting.
 //!
 //! Note: The word 'address' refers to either an account address or a
 //! contract address.
@@ -17,34 +40,291 @@
 //! function for transferring an amount of a specific token type from one
 //! address to another address. An address can enable and disable one or more
 //! addresses as operators. An operator of some address is allowed to transfer
-//! any tokens owned by this address.
+//! any tokens owned by this address. The parameter of `updateOperator` can end
+//! with an optional expiry, after which the added operators are no longer
+//! operators.
+//!
+//! The contract implements CIS3 sponsored transactions. The `permit` function
+//! executes a `transfer` or `updateOperator` message on behalf of an account,
+//! which signed the message with the keys of the account, as done by the
+//! wallets when signing a message. This allows another account to pay for the
+//! transaction.
+//!
+//! Tokens can be sold to the public through drops. An address with the `Admin`
+//! role configures a drop for a token type with `setDrop`, after which any
+//! account can buy tokens with `publicMint` while the drop is active. The
+//! payments are forwarded to the treasury account, set with `setTreasury`.
+//! A drop can also have an allowlist, set with `setAllowlist` as the root of a
+//! Merkle tree, such that allowlisted accounts can mint with `allowlistMint`
+//! up to their allowance. See the `merkle` module for building the tree.
+//!
+//! Tokens can also be minted lazily: an account with the `Minter` role signs a
+//! voucher off-chain, which the first buyer redeems with `redeemVoucher`,
+//! paying for the transaction and the price of the voucher.
+//!
+//! The contract stores the royalties owed to the creators when tokens are
+//! sold, as recipients and basis points of the sale price. The royalties of a
+//! token type are set when it is first minted, falling back to the royalties
+//! of the collection set at initialization. Marketplaces query the payments
+//! for a sale with `royaltyInfo`, and detect the support of this contract
+//! through `supports` with the `CIS2-Royalties` standard identifier.
 //!
-//! This contract also contains an example of a function to be called when
-//! receiving tokens. In which case the contract will forward the tokens to
-//! the contract owner.
-//! This function is not very useful and is only there to showcase a simple
-//! implementation of a token receive hook.
+//! Token types minted as soulbound cannot be transferred. Marketplaces query
+//! whether a token type is soulbound with `isSoulbound`, and detect the support
+//! of this contract through `supports` with the `CIS2-Soulbound` standard
+//! identifier.
+//!
+//! The contract is upgradable: an address with the `Admin` role can upgrade
+//! the contract to a new smart contract module with `upgrade`, optionally
+//! calling an entrypoint of the new module such as `migrate`, which migrates
+//! the state to the `STATE_VERSION` of the new module.
+//!
+//! The contract can receive CIS2 tokens of other contracts through
+//! `onReceivingCIS2`, according to the `ReceiveMode` set at initialization:
+//! received tokens are either rejected, kept in custody for the address they
+//! came from, which can withdraw them with `withdraw`, or forwarded to the
+//! admin.
 
 #![cfg_attr(not(feature = "std"), no_std)]
-use core::convert::TryInto;
+pub mod merkle;
 
 use concordium_cis2::*;
 use concordium_std::*;
+use merkle::MerkleHash;
+
+/// The standard identifier for the CIS-3: Sponsored Transactions standard.
+const CIS3_STANDARD_IDENTIFIER: StandardIdentifier<'static> =
+    StandardIdentifier::new_unchecked("CIS-3");
+
+/// The standard identifier for the royalty information of this contract,
+/// queried with `royaltyInfo`.
+const ROYALTIES_STANDARD_IDENTIFIER: StandardIdentifier<'static> =
+    StandardIdentifier::new_unchecked("CIS2-Royalties");
+
+/// The standard identifier for the soulbound token types of this contract,
+/// queried with `isSoulbound`.
+const SOULBOUND_STANDARD_IDENTIFIER: StandardIdentifier<'static> =
+    StandardIdentifier::new_unchecked("CIS2-Soulbound");
 
 /// List of supported standards by this contract address.
-const SUPPORTS_STANDARDS: [StandardIdentifier<'static>; 2] =
-    [CIS0_STANDARD_IDENTIFIER, CIS2_STANDARD_IDENTIFIER];
+const SUPPORTS_STANDARDS: [StandardIdentifier<'static>; 5] = [
+    CIS0_STANDARD_IDENTIFIER,
+    CIS2_STANDARD_IDENTIFIER,
+    CIS3_STANDARD_IDENTIFIER,
+    ROYALTIES_STANDARD_IDENTIFIER,
+    SOULBOUND_STANDARD_IDENTIFIER,
+];
+
+/// The roles granted to the admin at initialization, which are handed over
+/// to the new admin together with the `Admin` role.
+const ADMIN_ROLES: [Role; 3] = [Role::Minter, Role::MetadataUpdater, Role::Pauser];
+
+/// The basis points of the whole sale price.
+const MAX_BASIS_POINTS: u16 = 10_000;
+
+/// List of entrypoints supported by the `permit` function (CIS3).
+const SUPPORTS_PERMIT_ENTRYPOINTS: [EntrypointName<'static>; 2] = [
+    EntrypointName::new_unchecked("transfer"),
+    EntrypointName::new_unchecked("updateOperator"),
+];
+
+/// The domain separator of the vouchers signed for `redeemVoucher`, which
+/// prevents a voucher signature from being valid for any other signed message,
+/// such as a `permit` message.
+const VOUCHER_DOMAIN: &[u8] = b"redeemVoucher";
+
+/// The maximum number of items returned in a single page by the paginated
+/// views, regardless of the limit requested.
+const MAX_PAGE_LIMIT: u32 = 100;
+
+/// The version of the state layout of this module. It must be increased
+/// whenever the state layout changes, together with a migration in
+/// `contract_migrate`.
+///
+/// - Version 1: the first upgradable layout, see `StateV1`.
+/// - Version 2: soulbound token types, allowances, operator expiry, royalties
+///   and receive modes.
+const STATE_VERSION: u32 = 2;
 
 // Types
 
 /// Contract token ID type.
-/// To save bytes we use a small token ID type, but is limited to be represented
-/// by a `u8`.
-type ContractTokenId = TokenIdU8;
+/// To save bytes we use a small token ID type by default, but is limited to be
+/// represented by a `u8`. A wider token ID type can be selected using one of
+/// the cargo features `token-id-u32`, `token-id-u64` or `token-id-vec`.
+#[cfg(not(any(
+    feature = "token-id-u32",
+    feature = "token-id-u64",
+    feature = "token-id-vec"
+)))]
+pub type ContractTokenId = TokenIdU8;
+
+/// Contract token ID type, limited to be represented by a `u32`.
+#[cfg(feature = "token-id-u32")]
+pub type ContractTokenId = TokenIdU32;
+
+/// Contract token ID type, limited to be represented by a `u64`.
+#[cfg(feature = "token-id-u64")]
+pub type ContractTokenId = TokenIdU64;
+
+/// Contract token ID type, represented by a list of at most 255 bytes.
+#[cfg(feature = "token-id-vec")]
+pub type ContractTokenId = TokenIdVec;
+
+#[cfg(any(
+    all(feature = "token-id-u32", feature = "token-id-u64"),
+    all(feature = "token-id-u32", feature = "token-id-vec"),
+    all(feature = "token-id-u64", feature = "token-id-vec")
+))]
+compile_error!(
+    "At most one of the features `token-id-u32`, `token-id-u64` and `token-id-vec` can be enabled."
+);
+
+/// Construct a token ID of the `ContractTokenId` type selected by the cargo
+/// features from a single byte, such that the unit and integration tests work
+/// with every token ID type.
+#[cfg(not(any(
+    feature = "token-id-u32",
+    feature = "token-id-u64",
+    feature = "token-id-vec"
+)))]
+pub fn new_token_id(id: u8) -> ContractTokenId {
+    TokenIdU8(id)
+}
+
+/// Construct a token ID of the `ContractTokenId` type from a single byte, in
+/// the most significant byte of the `u32`.
+#[cfg(feature = "token-id-u32")]
+pub fn new_token_id(id: u8) -> ContractTokenId {
+    TokenIdU32(u32::from(id) << 24)
+}
+
+/// Construct a token ID of the `ContractTokenId` type from a single byte, in
+/// the most significant byte of the `u64`.
+#[cfg(feature = "token-id-u64")]
+pub fn new_token_id(id: u8) -> ContractTokenId {
+    TokenIdU64(u64::from(id) << 56)
+}
+
+/// Construct a token ID of the `ContractTokenId` type from a single byte,
+/// repeated to 40 bytes.
+#[cfg(feature = "token-id-vec")]
+pub fn new_token_id(id: u8) -> ContractTokenId {
+    TokenIdVec(vec![id; 40])
+}
 
 /// Contract token amount type.
 type ContractTokenAmount = TokenAmountU64;
 
+/// The parameter for minting a new token type.
+#[derive(Serial, Deserial, SchemaType)]
+struct TokenMintParams {
+    /// The metadata of the token type, `None` to derive the metadata URL from
+    /// the base URL.
+    metadata: Option<TokenMetadata>,
+    /// The maximum supply of the token type. It is fixed when the token type
+    /// is first minted.
+    max_supply: ContractTokenAmount,
+    /// The amount of tokens to mint.
+    amount: ContractTokenAmount,
+    /// Whether the tokens of the token type are non-transferable. It is fixed
+    /// when the token type is first minted.
+    soulbound: bool,
+    /// The royalties of the token type, `None` to use the royalties of the
+    /// collection. It is fixed when the token type is first minted.
+    royalties: Option<Vec<Royalty>>,
+}
+
+/// The parameter for the contract function `init`.
+#[derive(Serial, Deserial, SchemaType)]
+struct InitParams {
+    /// The name of the collection.
+    name: String,
+    /// The symbol of the collection.
+    symbol: String,
+    /// The metadata of the collection, if any.
+    metadata: Option<TokenMetadata>,
+    /// The address granted every role, `None` to grant every role to the
+    /// account instantiating the contract.
+    admin: Option<Address>,
+    /// The base URL the metadata URLs of the token types are derived from,
+    /// `None` if every token type has an explicit metadata URL.
+    base_url: Option<String>,
+    /// The royalties of the token types minted without royalties.
+    royalties: Vec<Royalty>,
+    /// How CIS2 tokens received by the contract are handled.
+    receive_mode: ReceiveMode,
+}
+
+/// How CIS2 tokens received through `onReceivingCIS2` are handled.
+#[derive(Debug, Serialize, SchemaType, PartialEq, Eq, Clone, Copy)]
+enum ReceiveMode {
+    /// Received tokens are rejected.
+    Reject,
+    /// Received tokens are kept in custody for the address they came from,
+    /// which can withdraw them.
+    Custody,
+    /// Received tokens are forwarded to the admin of this contract. An admin
+    /// which is a contract receives them through its `onReceivingCIS2`
+    /// entrypoint, which it must implement.
+    ForwardToAdmin,
+}
+
+/// The tokens of a token contract kept in custody for a depositor.
+#[derive(Debug, Serialize, SchemaType, Clone)]
+struct DepositKey {
+    /// The contract of the tokens.
+    token_contract: ContractAddress,
+    /// The token ID in the contract of the tokens.
+    token_id: TokenIdVec,
+    /// The address the tokens came from.
+    depositor: Address,
+}
+
+/// The parameter for the contract function `withdraw`.
+#[derive(Serialize, SchemaType)]
+struct WithdrawParams {
+    /// The contract of the tokens.
+    token_contract: ContractAddress,
+    /// The token ID in the contract of the tokens.
+    token_id: TokenIdVec,
+    /// The amount of tokens to withdraw.
+    amount: ContractTokenAmount,
+    /// The receiver of the tokens.
+    to: Receiver,
+}
+
+/// The parameter for the contract function `upgrade`.
+#[derive(Serialize, SchemaType)]
+struct UpgradeParams {
+    /// The reference of the module to upgrade to.
+    module: ModuleReference,
+    /// An optional entrypoint to call in the new module after the upgrade,
+    /// together with its parameter.
+    migrate: Option<(OwnedEntrypointName, OwnedParameter)>,
+}
+
+/// The parameter for the contract function `commitReveal`.
+#[derive(Serial, Deserial, SchemaType)]
+struct CommitRevealParams {
+    /// The metadata URL of every token type without an explicit metadata URL
+    /// until the reveal.
+    placeholder: MetadataUrl,
+    /// The SHA256 hash of the serialized `RevealParams` of the reveal.
+    commitment: [u8; 32],
+}
+
+/// The parameter for the contract function `reveal`.
+#[derive(Debug, Serialize, SchemaType)]
+struct RevealParams {
+    /// The final base URL the metadata URLs of the token types are derived
+    /// from.
+    base_url: String,
+    /// The hash of the final metadata set, allowing collectors to verify the
+    /// metadata of every token type.
+    provenance_hash: [u8; 32],
+}
+
 /// The parameter for the contract function `mint` which mints a number of
 /// token types and/or amounts of tokens to a given address.
 #[derive(Serial, Deserial, SchemaType)]
@@ -52,7 +332,253 @@ struct MintParams {
     /// Owner of the newly minted tokens.
     owner: Address,
     /// A collection of tokens to mint.
-    tokens: collections::BTreeMap<ContractTokenId, (TokenMetadata, ContractTokenAmount)>,
+    tokens: collections::BTreeMap<ContractTokenId, TokenMintParams>,
+}
+
+/// The parameter for the contract function `mintMore` which mints additional
+/// amounts of existing token types to a given address.
+#[derive(Serial, Deserial, SchemaType)]
+struct MintMoreParams {
+    /// Owner of the newly minted tokens.
+    owner: Address,
+    /// The amount of tokens to mint for each token type.
+    tokens: collections::BTreeMap<ContractTokenId, ContractTokenAmount>,
+}
+
+/// The parameter for the contract function `burn` which burns an amount of a
+/// token type owned by a given address.
+#[derive(Serial, Deserial, SchemaType)]
+struct BurnParams {
+    /// Owner of the tokens to burn.
+    owner: Address,
+    /// The token type to burn.
+    token_id: ContractTokenId,
+    /// The amount of tokens to burn.
+    amount: ContractTokenAmount,
+}
+
+/// The parameter for the contract function `approve` which sets the amount of
+/// tokens of a token type a spender can transfer on behalf of the sender.
+#[derive(Serial, Deserial, SchemaType)]
+struct ApproveParams {
+    /// The address allowed to transfer the tokens.
+    spender: Address,
+    /// The token type the allowance applies to.
+    token_id: ContractTokenId,
+    /// The amount of tokens the spender can transfer, replacing any previous
+    /// allowance. Zero removes the allowance.
+    amount: ContractTokenAmount,
+}
+
+/// The parameter for the contract function `updateOperatorWithExpiry`.
+#[derive(Serialize, SchemaType)]
+struct UpdateOperatorWithExpiryParams {
+    /// The operator updates.
+    updates: UpdateOperatorParams,
+    /// The time at which the added operators expire.
+    expiry: Timestamp,
+}
+
+/// The configuration of a drop, selling tokens of a token type to the public.
+#[derive(Debug, Serialize, Clone, SchemaType, PartialEq, Eq)]
+struct DropConfig {
+    /// The price of a single token.
+    price: Amount,
+    /// The maximum amount of tokens a single account can mint in the drop.
+    max_per_wallet: ContractTokenAmount,
+    /// The maximum amount of tokens which can be minted in the drop.
+    max_minted: ContractTokenAmount,
+    /// The time the drop opens.
+    start: Timestamp,
+    /// The time the drop closes.
+    end: Timestamp,
+}
+
+/// The parameter for the contract function `setDrop`.
+#[derive(Serial, Deserial, SchemaType)]
+struct SetDropParams {
+    /// The token type sold in the drop.
+    token_id: ContractTokenId,
+    /// The configuration of the drop.
+    config: DropConfig,
+}
+
+/// The parameter for the contract function `publicMint`.
+#[derive(Serial, Deserial, SchemaType)]
+struct PublicMintParams {
+    /// The token type to mint.
+    token_id: ContractTokenId,
+    /// The amount of tokens to mint.
+    amount: ContractTokenAmount,
+}
+
+/// The parameter for the contract function `setAllowlist`.
+#[derive(Serial, Deserial, SchemaType)]
+struct SetAllowlistParams {
+    /// The token type sold in the drop.
+    token_id: ContractTokenId,
+    /// The root of the Merkle tree over the allowlist, `None` to remove the
+    /// allowlist.
+    root: Option<MerkleHash>,
+}
+
+/// The parameter for the contract function `allowlistMint`.
+#[derive(Serial, Deserial, SchemaType)]
+struct AllowlistMintParams {
+    /// The token type to mint.
+    token_id: ContractTokenId,
+    /// The amount of tokens to mint.
+    amount: ContractTokenAmount,
+    /// The total amount of tokens the sender is allowed to mint, as listed in
+    /// the allowlist.
+    allowance: ContractTokenAmount,
+    /// The proof that the sender and its allowance is part of the allowlist.
+    #[concordium(size_length = 1)]
+    proof: Vec<MerkleHash>,
+}
+
+/// The parameter for updating the metadata of a token type.
+#[derive(Serial, Deserial, SchemaType)]
+struct UpdateTokenMetadataParam {
+    /// The token type to update the metadata of.
+    token_id: ContractTokenId,
+    /// The new metadata of the token type.
+    metadata: TokenMetadata,
+}
+
+/// The parameter for the contract function `updateTokenMetadata`.
+#[derive(Serial, Deserial, SchemaType)]
+struct UpdateTokenMetadataParams(#[concordium(size_length = 2)] Vec<UpdateTokenMetadataParam>);
+
+/// The parameter for the contract function `freezeMetadata`.
+#[derive(Serial, Deserial, SchemaType)]
+enum FreezeMetadataParams {
+    /// Freeze the metadata of every token type in the contract, including
+    /// token types minted later.
+    Collection,
+    /// Freeze the metadata of the given token types.
+    Tokens(#[concordium(size_length = 2)] Vec<ContractTokenId>),
+}
+
+/// Part of the parameter type for the contract function `permit`.
+/// Specifies the message that is signed.
+#[derive(Serialize, SchemaType)]
+struct PermitMessage {
+    /// The contract address that the signature is intended for.
+    contract_address: ContractAddress,
+    /// A nonce to prevent replay attacks.
+    nonce: u64,
+    /// A timestamp after which the signature expires.
+    timestamp: Timestamp,
+    /// The entrypoint that the signature is intended for.
+    entry_point: OwnedEntrypointName,
+    /// The serialized payload that should be forwarded to either the
+    /// `transfer` or the `updateOperator` function.
+    #[concordium(size_length = 2)]
+    payload: Vec<u8>,
+}
+
+/// The parameter type for the contract function `permit`.
+#[derive(Serialize, SchemaType)]
+struct PermitParam {
+    /// Signatures of the signer over the hash computed by
+    /// `permit_message_hash`, checked against the keys of the signer account.
+    signature: AccountSignatures,
+    /// Account that created the above signature.
+    signer: AccountAddress,
+    /// Message that was signed.
+    message: PermitMessage,
+}
+
+/// The parameter type for the contract function `viewMessageHash`.
+#[derive(Serialize, SchemaType)]
+struct ViewMessageHashParams {
+    /// Account signing the message.
+    signer: AccountAddress,
+    /// Message to sign.
+    message: PermitMessage,
+}
+
+/// The hash signed by the signer of a `permit` message: the SHA256 hash of
+/// the address of the signer, followed by 8 zero bytes and the serialized
+/// message. The wallets prepend a signed message with the account address and
+/// 8 zero bytes, where a transaction has the non-zero nonce of the account, so
+/// a signed message cannot be a valid transaction.
+fn permit_message_hash(
+    signer: &AccountAddress,
+    message: &PermitMessage,
+    crypto_primitives: &impl HasCryptoPrimitives,
+) -> [u8; 32] {
+    let mut bytes = signer.0.to_vec();
+    bytes.extend_from_slice(&[0u8; 8]);
+    bytes.extend_from_slice(&to_bytes(message));
+    crypto_primitives.hash_sha2_256(&bytes).0
+}
+
+/// Part of the parameter type for the contract function `redeemVoucher`.
+/// Specifies the tokens the voucher can be redeemed for.
+#[derive(Serialize, SchemaType)]
+struct MintVoucher {
+    /// The contract address that the voucher is intended for.
+    contract_address: ContractAddress,
+    /// The token type to mint.
+    token_id: ContractTokenId,
+    /// The metadata of the token type, used if the token type is not minted
+    /// yet.
+    metadata: TokenMetadata,
+    /// The maximum supply of the token type, used if the token type is not
+    /// minted yet.
+    max_supply: ContractTokenAmount,
+    /// The amount of tokens to mint.
+    amount: ContractTokenAmount,
+    /// The price to pay for the tokens.
+    price: Amount,
+    /// A timestamp after which the voucher expires.
+    expiry: Timestamp,
+    /// A nonce to prevent redeeming the voucher more than once.
+    nonce: u64,
+}
+
+/// The message signed for a voucher: the serialized address of the contract,
+/// followed by the bytes of `VOUCHER_DOMAIN` and the serialized voucher.
+fn voucher_message(contract_address: &ContractAddress, voucher: &MintVoucher) -> Vec<u8> {
+    let mut message = to_bytes(contract_address);
+    message.extend_from_slice(VOUCHER_DOMAIN);
+    message.extend_from_slice(&to_bytes(voucher));
+    message
+}
+
+/// The parameter type for the contract function `redeemVoucher`.
+#[derive(Serialize, SchemaType)]
+struct RedeemVoucherParams {
+    /// Signature of the signer over the SHA256 hash of the message computed
+    /// by `voucher_message`.
+    signature: SignatureEd25519,
+    /// Account that created the above signature.
+    signer: AccountAddress,
+    /// Voucher that was signed.
+    voucher: MintVoucher,
+}
+
+/// The parameter type for the contract function `nonceOf`.
+#[derive(Serialize, SchemaType)]
+struct NonceOfQueryParams {
+    /// List of accounts to query the next nonce of.
+    #[concordium(size_length = 2)]
+    queries: Vec<AccountAddress>,
+}
+
+/// The response type for the contract function `nonceOf`.
+/// The nonces are in the same order as the queried accounts.
+#[derive(Debug, Serialize, SchemaType)]
+struct NonceOfQueryResponse(#[concordium(size_length = 2)] Vec<u64>);
+
+/// The parameter type for the contract function `supportsPermit`.
+#[derive(Serialize, SchemaType)]
+struct SupportsPermitQueryParams {
+    /// The list of entrypoints to query support for.
+    #[concordium(size_length = 2)]
+    queries: Vec<OwnedEntrypointName>,
 }
 
 /// The parameter type for the contract function `setImplementors`.
@@ -67,22 +593,81 @@ struct SetImplementorsParams {
 }
 
 /// The state for each address.
-#[derive(Serial, DeserialWithState, Deletable, StateClone)]
+#[derive(Serial, DeserialWithState, Deletable)]
 #[concordium(state_parameter = "S")]
 struct AddressState<S> {
     /// The amount of tokens owned by this address.
     balances: StateMap<ContractTokenId, ContractTokenAmount, S>,
-    /// The address which are currently enabled as operators for this address.
-    operators: StateSet<Address, S>,
+    /// The address which are currently enabled as operators for this address,
+    /// and the time at which they expire, if any.
+    operators: StateMap<Address, Option<Timestamp>, S>,
+    /// The amount of tokens of a token type each spender can transfer on
+    /// behalf of this address.
+    allowances: StateMap<(Address, ContractTokenId), ContractTokenAmount, S>,
 }
 
 impl<S: HasStateApi> AddressState<S> {
     fn empty(state_builder: &mut StateBuilder<S>) -> Self {
         AddressState {
             balances: state_builder.new_map(),
-            operators: state_builder.new_set(),
+            operators: state_builder.new_map(),
+            allowances: state_builder.new_map(),
+        }
+    }
+}
+
+/// The state of a drop of a token type.
+#[derive(Serial, DeserialWithState)]
+#[concordium(state_parameter = "S")]
+struct DropState<S> {
+    /// The configuration of the drop.
+    config: DropConfig,
+    /// The amount of tokens minted in the drop.
+    minted: ContractTokenAmount,
+    /// The amount of tokens minted in the drop by each account.
+    minted_by: StateMap<AccountAddress, ContractTokenAmount, S>,
+    /// The root of the Merkle tree over the allowlist of the drop, if any.
+    allowlist_root: Option<MerkleHash>,
+    /// The amount of tokens claimed through the allowlist by each account.
+    claimed: StateMap<AccountAddress, ContractTokenAmount, S>,
+}
+
+impl<S: HasStateApi> DropState<S> {
+    fn new(config: DropConfig, state_builder: &mut StateBuilder<S>) -> Self {
+        DropState {
+            config,
+            minted: 0.into(),
+            minted_by: state_builder.new_map(),
+            allowlist_root: None,
+            claimed: state_builder.new_map(),
         }
     }
+
+    /// Record an amount of tokens minted in the drop and return the price of
+    /// the tokens.
+    /// Results in an error if the drop is not open at the given time or if
+    /// the amount exceeds the tokens remaining in the drop.
+    fn record_mint(
+        &mut self,
+        amount: ContractTokenAmount,
+        now: Timestamp,
+    ) -> ContractResult<Amount> {
+        ensure!(
+            self.config.start <= now && now < self.config.end,
+            ContractError::Custom(CustomContractError::DropNotActive)
+        );
+        ensure!(
+            self.minted <= self.config.max_minted && amount <= self.config.max_minted - self.minted,
+            ContractError::Custom(CustomContractError::DropSoldOut)
+        );
+        self.minted += amount;
+        self.config
+            .price
+            .micro_ccd
+            .checked_mul(amount.0)
+            .map(Amount::from_micro_ccd)
+            .ok_or(ContractError::Custom(CustomContractError::IncorrectPayment))
+    }
 }
 
 #[derive(Debug, Serialize, Clone, SchemaType)]
@@ -90,47 +675,248 @@ pub struct TokenMetadata {
     /// The URL following the specification RFC1738.
     #[concordium(size_length = 2)]
     pub url: String,
-    /// A optional hash of the content.
-    #[concordium(size_length = 2)]
-    pub hash: String,
+    /// An optional SHA256 hash of the content, `None` if the content has no
+    /// hash.
+    pub hash: Option<[u8; 32]>,
 }
 
 impl TokenMetadata {
-    fn get_hash_bytes(&self) -> Option<[u8; 32]> {
-        match hex::decode(&self.hash) {
-            Ok(v) => {
-                let slice = v.as_slice();
-                match slice.try_into() {
-                    Ok(array) => Option::Some(array),
-                    Err(_) => Option::None,
-                }
-            }
-            Err(_) => Option::None,
-        }
+    /// Check the hash of the content is valid.
+    /// A hash of all zeros is rejected, as it is a placeholder rather than the
+    /// hash of any content.
+    fn ensure_valid_hash(&self) -> ContractResult<()> {
+        ensure!(
+            self.hash != Some([0u8; 32]),
+            ContractError::Custom(CustomContractError::InvalidMetadataHash)
+        );
+        Ok(())
     }
 
     fn to_metadata_url(&self) -> MetadataUrl {
         MetadataUrl {
             url: self.url.to_string(),
-            hash: self.get_hash_bytes(),
+            hash: self.hash,
         }
     }
 }
 
+/// A royalty owed to a recipient when a token is sold.
+#[derive(Debug, Serialize, Clone, SchemaType, PartialEq, Eq)]
+struct Royalty {
+    /// The account receiving the royalty.
+    recipient: AccountAddress,
+    /// The share of the sale price, in basis points.
+    basis_points: u16,
+}
+
+impl Royalty {
+    /// The amount owed to the recipient for the given sale price, rounded
+    /// down.
+    fn payment(&self, sale_price: Amount) -> Amount {
+        let micro_ccd = u128::from(sale_price.micro_ccd) * u128::from(self.basis_points)
+            / u128::from(MAX_BASIS_POINTS);
+        Amount::from_micro_ccd(micro_ccd as u64)
+    }
+}
+
+/// Check the royalties sum to at most the whole sale price.
+fn ensure_valid_royalties(royalties: &[Royalty]) -> ContractResult<()> {
+    let total: u32 = royalties
+        .iter()
+        .map(|royalty| u32::from(royalty.basis_points))
+        .sum();
+    ensure!(
+        total <= u32::from(MAX_BASIS_POINTS),
+        ContractError::Custom(CustomContractError::InvalidRoyalty)
+    );
+    Ok(())
+}
+
+/// The roles which can be granted to addresses.
+#[derive(Debug, Serialize, SchemaType, PartialEq, Eq, Clone, Copy)]
+enum Role {
+    /// Can grant and revoke roles. Held only by the admin of the contract and
+    /// handed over with `transferAdmin` and `acceptAdmin`.
+    Admin,
+    /// Can mint tokens.
+    Minter,
+    /// Can update the metadata of tokens.
+    MetadataUpdater,
+    /// Can pause and unpause the contract.
+    Pauser,
+}
+
+/// The parameter type for the contract functions `grantRole` and `revokeRole`
+/// and the queries of `hasRole`.
+#[derive(Debug, Serialize, SchemaType)]
+struct RoleParams {
+    /// The address to grant the role to, revoke the role from or query.
+    address: Address,
+    /// The role.
+    role: Role,
+}
+
+/// The information about the collection, set when initializing the contract.
+#[derive(Debug, Serialize, Clone, SchemaType, Default)]
+struct CollectionInfo {
+    /// The name of the collection.
+    name: String,
+    /// The symbol of the collection.
+    symbol: String,
+    /// The metadata URL of the collection, if any.
+    metadata_url: Option<MetadataUrl>,
+}
+
+/// The state for each token type.
+#[derive(Debug, Serialize, Clone)]
+struct TokenState {
+    /// The explicit metadata URL of the token type, `None` if it is derived
+    /// from the base URL.
+    metadata_url: Option<MetadataUrl>,
+    /// The total amount of tokens of this type currently in circulation.
+    supply: ContractTokenAmount,
+    /// The maximum amount of tokens of this type which can be in circulation.
+    max_supply: ContractTokenAmount,
+    /// Whether the metadata URL of the token type is permanently locked.
+    metadata_frozen: bool,
+    /// Whether the tokens of this type are non-transferable.
+    soulbound: bool,
+    /// The royalties of this type, `None` if the royalties of the collection
+    /// apply.
+    royalties: Option<Vec<Royalty>>,
+}
+
 /// The contract state,
 ///
 /// Note: The specification does not specify how to structure the contract state
 /// and this could be structured in a more space efficient way.
-#[derive(Serial, DeserialWithState, StateClone)]
+#[derive(Serial, DeserialWithState)]
 #[concordium(state_parameter = "S")]
 struct State<S> {
+    /// The version of the state layout, see `STATE_VERSION`.
+    version: u32,
+    /// The information about the collection.
+    collection: CollectionInfo,
+    /// The admin of the contract, which can hand over the contract.
+    admin: Address,
+    /// The address proposed as the new admin, until it accepts.
+    pending_admin: Option<Address>,
     /// The state of addresses.
     state: StateMap<Address, AddressState<S>, S>,
-    /// All of the token IDs
-    tokens: StateMap<ContractTokenId, MetadataUrl, S>,
+    /// All of the token IDs and the state of each token type.
+    tokens: StateMap<ContractTokenId, TokenState, S>,
+    /// The addresses with a non-zero balance of each token type.
+    holders: StateMap<ContractTokenId, StateSet<Address, S>, S>,
+    /// Map with contract addresses providing implementations of additional
+    /// standards.
+    implementors: StateMap<StandardIdentifierOwned, Vec<ContractAddress>, S>,
+    /// The roles granted to each address.
+    roles: StateSet<(Address, Role), S>,
+    /// Whether the metadata URLs of every token type are permanently locked.
+    metadata_frozen: bool,
+    /// Whether minting, burning and transferring tokens is halted.
+    paused: bool,
+    /// The next nonce of each account signing `permit` messages.
+    nonces_registry: StateMap<AccountAddress, u64, S>,
+    /// The public key each account uses to sign vouchers for `redeemVoucher`.
+    public_keys: StateMap<AccountAddress, PublicKeyEd25519, S>,
+    /// The drops selling token types to the public.
+    drops: StateMap<ContractTokenId, DropState<S>, S>,
+    /// The account receiving the payments of the drops and vouchers.
+    treasury: AccountAddress,
+    /// The nonces of the vouchers which have been redeemed, for each signer.
+    redeemed_vouchers: StateSet<(AccountAddress, u64), S>,
+    /// The base URL the metadata URLs of the token types are derived from.
+    base_url: Option<String>,
+    /// The metadata URL of every token type without an explicit metadata URL
+    /// until the reveal.
+    placeholder: Option<MetadataUrl>,
+    /// The commitment to the `RevealParams` of the reveal.
+    reveal_commitment: Option<[u8; 32]>,
+    /// Whether the reveal has happened.
+    revealed: bool,
+    /// The royalties of the token types without royalties of their own.
+    royalties: Vec<Royalty>,
+    /// How CIS2 tokens received by the contract are handled.
+    receive_mode: ReceiveMode,
+    /// The CIS2 tokens of other contracts kept in custody for each depositor.
+    deposits: StateMap<DepositKey, ContractTokenAmount, S>,
+}
+
+/// The state for each address in state version 1.
+#[derive(Serial, DeserialWithState)]
+#[concordium(state_parameter = "S")]
+struct AddressStateV1<S> {
+    /// The amount of tokens owned by this address.
+    balances: StateMap<ContractTokenId, ContractTokenAmount, S>,
+    /// The address which are currently enabled as operators for this address.
+    operators: StateSet<Address, S>,
+}
+
+/// The state for each token type in state version 1.
+#[derive(Serialize, Clone)]
+struct TokenStateV1 {
+    /// The explicit metadata URL of the token type, `None` if it is derived
+    /// from the base URL.
+    metadata_url: Option<MetadataUrl>,
+    /// The total amount of tokens of this type currently in circulation.
+    supply: ContractTokenAmount,
+    /// The maximum amount of tokens of this type which can be in circulation.
+    max_supply: ContractTokenAmount,
+    /// Whether the metadata URL of the token type is permanently locked.
+    metadata_frozen: bool,
+}
+
+/// The contract state in state version 1, read by `migrate` after upgrading
+/// from a module with this state version.
+#[derive(DeserialWithState)]
+#[concordium(state_parameter = "S")]
+struct StateV1<S> {
+    /// The version of the state layout, always 1. It is read separately by
+    /// `contract_migrate` to determine the layout of the state.
+    #[allow(dead_code)]
+    version: u32,
+    /// The information about the collection.
+    collection: CollectionInfo,
+    /// The admin of the contract, which can hand over the contract.
+    admin: Address,
+    /// The address proposed as the new admin, until it accepts.
+    pending_admin: Option<Address>,
+    /// The state of addresses.
+    state: StateMap<Address, AddressStateV1<S>, S>,
+    /// All of the token IDs and the state of each token type.
+    tokens: StateMap<ContractTokenId, TokenStateV1, S>,
+    /// The addresses with a non-zero balance of each token type.
+    holders: StateMap<ContractTokenId, StateSet<Address, S>, S>,
     /// Map with contract addresses providing implementations of additional
     /// standards.
     implementors: StateMap<StandardIdentifierOwned, Vec<ContractAddress>, S>,
+    /// The roles granted to each address, including the `Admin` role of the
+    /// admin.
+    roles: StateSet<(Address, Role), S>,
+    /// Whether the metadata URLs of every token type are permanently locked.
+    metadata_frozen: bool,
+    /// Whether minting, burning and transferring tokens is halted.
+    paused: bool,
+    /// The next nonce of each account signing `permit` messages.
+    nonces_registry: StateMap<AccountAddress, u64, S>,
+    /// The public key each account uses to sign vouchers for `redeemVoucher`.
+    public_keys: StateMap<AccountAddress, PublicKeyEd25519, S>,
+    /// The drops selling token types to the public.
+    drops: StateMap<ContractTokenId, DropState<S>, S>,
+    /// The account receiving the payments of the drops and vouchers.
+    treasury: AccountAddress,
+    /// The nonces of the vouchers which have been redeemed, for each signer.
+    redeemed_vouchers: StateSet<(AccountAddress, u64), S>,
+    /// The base URL the metadata URLs of the token types are derived from.
+    base_url: Option<String>,
+    /// The metadata URL of every token type without an explicit metadata URL
+    /// until the reveal.
+    placeholder: Option<MetadataUrl>,
+    /// The commitment to the `RevealParams` of the reveal.
+    reveal_commitment: Option<[u8; 32]>,
+    /// Whether the reveal has happened.
+    revealed: bool,
 }
 
 /// The different errors the contract can produce.
@@ -149,7 +935,280 @@ enum CustomContractError {
     ContractOnly,
     /// Failed to invoke a contract.
     InvokeContractError,
+    /// The token ID is already minted, use `mintMore` to increase its supply.
     TokenAlreadyMinted,
+    /// Minting would increase the supply of a token type above its maximum
+    /// supply.
+    MaxSupplyExceeded,
+    /// The metadata of the token type is frozen and cannot be updated.
+    MetadataFrozen,
+    /// The contract is paused.
+    Paused,
+    /// Only an account can call this function.
+    AccountOnly,
+    /// The signer has not registered a public key.
+    MissingPublicKey,
+    /// The signature of the `permit` message is invalid.
+    WrongSignature,
+    /// The nonce of the `permit` message does not match the nonce of the
+    /// signer.
+    NonceMismatch,
+    /// The `permit` message is intended for a different contract.
+    WrongContract,
+    /// The `permit` message is intended for an unsupported entrypoint.
+    WrongEntryPoint,
+    /// The `permit` message has expired.
+    Expired,
+    /// The hash of the token metadata is invalid.
+    InvalidMetadataHash,
+    /// The token type has no explicit metadata URL and no base URL is set.
+    MissingMetadataUrl,
+    /// A reveal has already been committed to.
+    RevealAlreadyCommitted,
+    /// No reveal has been committed to.
+    RevealNotCommitted,
+    /// The reveal has already happened.
+    AlreadyRevealed,
+    /// The reveal does not match the commitment.
+    InvalidReveal,
+    /// The metadata is hidden until the reveal.
+    NotRevealed,
+    /// Upgrade failed because the new module does not exist.
+    FailedUpgradeMissingModule,
+    /// Upgrade failed because the new module does not contain a contract with
+    /// a matching name.
+    FailedUpgradeMissingContract,
+    /// Upgrade failed because the smart contract version of the module is not
+    /// supported.
+    FailedUpgradeUnsupportedModuleVersion,
+    /// The state was written by a newer version of the contract.
+    UnsupportedStateVersion,
+    /// No drop is configured for the token type.
+    DropNotFound,
+    /// The drop is not open at the current time.
+    DropNotActive,
+    /// Minting would exceed the amount of tokens an account can mint in the
+    /// drop.
+    WalletLimitExceeded,
+    /// Minting would exceed the amount of tokens which can be minted in the
+    /// drop.
+    DropSoldOut,
+    /// The amount of CCD sent does not match the price of the tokens.
+    IncorrectPayment,
+    /// Failed to transfer CCD to an account.
+    InvokeTransferError,
+    /// The drop has no allowlist.
+    AllowlistNotSet,
+    /// The proof of the allowlist entry is invalid.
+    InvalidProof,
+    /// Minting would exceed the allowance of the account in the allowlist.
+    AllowanceExceeded,
+    /// The voucher has already been redeemed.
+    VoucherAlreadyRedeemed,
+    /// The token type is soulbound and its tokens cannot be transferred.
+    Soulbound,
+    /// The royalties sum to more than the whole sale price.
+    InvalidRoyalty,
+    /// The contract does not accept CIS2 tokens.
+    ReceiveRejected,
+    /// The `Admin` role cannot be granted or revoked, use `transferAdmin`.
+    AdminRoleNotGrantable,
+    /// The expiry of the operators has already passed.
+    InvalidExpiry,
+    /// The signer account of a `permit` message does not exist.
+    MissingAccount,
+}
+
+/// Tag for the custom `RoleGranted` event.
+const ROLE_GRANTED_EVENT_TAG: u8 = 0;
+/// Tag for the custom `RoleRevoked` event.
+const ROLE_REVOKED_EVENT_TAG: u8 = 1;
+/// Tag for the custom `MetadataFrozen` event.
+const METADATA_FROZEN_EVENT_TAG: u8 = 2;
+/// Tag for the custom `Revealed` event.
+const REVEALED_EVENT_TAG: u8 = 3;
+/// Tag for the custom `AdminTransferProposed` event.
+const ADMIN_TRANSFER_PROPOSED_EVENT_TAG: u8 = 4;
+/// Tag for the custom `AdminChanged` event.
+const ADMIN_CHANGED_EVENT_TAG: u8 = 5;
+/// Tag for the custom `Approval` event.
+const APPROVAL_EVENT_TAG: u8 = 6;
+/// Tag for the custom `Deposit` event.
+const DEPOSIT_EVENT_TAG: u8 = 7;
+/// Tag for the custom `Withdraw` event.
+const WITHDRAW_EVENT_TAG: u8 = 8;
+/// Tag for the custom `Forward` event.
+const FORWARD_EVENT_TAG: u8 = 9;
+/// Tag for the CIS3 `Nonce` event.
+const NONCE_EVENT_TAG: u8 = 250;
+
+/// The events logged by this contract in addition to the CIS2 events.
+/// The tags of these events do not overlap with the tags of the CIS2 events.
+#[derive(Debug)]
+enum CustomEvent {
+    /// A role was granted to an address.
+    RoleGranted(RoleEvent),
+    /// A role was revoked from an address.
+    RoleRevoked(RoleEvent),
+    /// The metadata of a token type or of every token type was frozen.
+    MetadataFrozen(MetadataFrozenEvent),
+    /// The final metadata of the token types was revealed.
+    Revealed(RevealParams),
+    /// A new admin was proposed, or the proposal was cancelled.
+    AdminTransferProposed(AdminTransferProposedEvent),
+    /// The proposed admin accepted and replaced the previous admin.
+    AdminChanged(AdminChangedEvent),
+    /// An owner set the allowance of a spender for a token type.
+    Approval(ApprovalEvent),
+    /// CIS2 tokens were received and kept in custody.
+    Deposit(DepositEvent),
+    /// CIS2 tokens kept in custody were withdrawn.
+    Withdraw(WithdrawEvent),
+    /// CIS2 tokens were received and forwarded to the admin.
+    Forward(ForwardEvent),
+    /// A `permit` message of an account was executed.
+    Nonce(NonceEvent),
+}
+
+/// The event logged when a role is granted or revoked.
+#[derive(Debug, Serialize, SchemaType)]
+struct RoleEvent {
+    /// The address the role was granted to or revoked from.
+    address: Address,
+    /// The role.
+    role: Role,
+}
+
+/// The event logged when metadata is frozen.
+#[derive(Debug, Serialize, SchemaType)]
+struct MetadataFrozenEvent {
+    /// The token type which metadata was frozen, or `None` if the metadata of
+    /// every token type was frozen.
+    token_id: Option<ContractTokenId>,
+}
+
+/// The event logged when a new admin is proposed.
+#[derive(Debug, Serialize, SchemaType)]
+struct AdminTransferProposedEvent {
+    /// The current admin.
+    admin: Address,
+    /// The proposed admin, or `None` if the proposal was cancelled.
+    pending_admin: Option<Address>,
+}
+
+/// The event logged when the admin changes.
+#[derive(Debug, Serialize, SchemaType)]
+struct AdminChangedEvent {
+    /// The previous admin.
+    previous_admin: Address,
+    /// The new admin.
+    new_admin: Address,
+}
+
+/// The event logged when an allowance is set.
+#[derive(Debug, Serialize, SchemaType)]
+struct ApprovalEvent {
+    /// The owner of the tokens.
+    owner: Address,
+    /// The address allowed to transfer the tokens.
+    spender: Address,
+    /// The token type the allowance applies to.
+    token_id: ContractTokenId,
+    /// The new allowance.
+    amount: ContractTokenAmount,
+}
+
+/// The event logged when CIS2 tokens are received and kept in custody.
+#[derive(Debug, Serialize, SchemaType)]
+struct DepositEvent {
+    /// The tokens and the address they came from.
+    deposit: DepositKey,
+    /// The amount of tokens received.
+    amount: ContractTokenAmount,
+}
+
+/// The event logged when CIS2 tokens kept in custody are withdrawn.
+#[derive(Debug, Serialize, SchemaType)]
+struct WithdrawEvent {
+    /// The tokens and the address withdrawing them.
+    deposit: DepositKey,
+    /// The amount of tokens withdrawn.
+    amount: ContractTokenAmount,
+    /// The address receiving the tokens.
+    to: Address,
+}
+
+/// The event logged when CIS2 tokens are received and forwarded to the admin.
+#[derive(Debug, Serialize, SchemaType)]
+struct ForwardEvent {
+    /// The contract of the tokens.
+    token_contract: ContractAddress,
+    /// The token ID in the contract of the tokens.
+    token_id: TokenIdVec,
+    /// The amount of tokens forwarded.
+    amount: ContractTokenAmount,
+    /// The admin receiving the tokens.
+    to: Address,
+}
+
+/// The CIS3 event logged when a `permit` message is executed.
+#[derive(Debug, Serialize, SchemaType)]
+struct NonceEvent {
+    /// The account that signed the message.
+    account: AccountAddress,
+    /// The nonce of the executed message.
+    nonce: u64,
+}
+
+impl Serial for CustomEvent {
+    fn serial<W: Write>(&self, out: &mut W) -> Result<(), W::Err> {
+        match self {
+            CustomEvent::RoleGranted(event) => {
+                out.write_u8(ROLE_GRANTED_EVENT_TAG)?;
+                event.serial(out)
+            }
+            CustomEvent::RoleRevoked(event) => {
+                out.write_u8(ROLE_REVOKED_EVENT_TAG)?;
+                event.serial(out)
+            }
+            CustomEvent::MetadataFrozen(event) => {
+                out.write_u8(METADATA_FROZEN_EVENT_TAG)?;
+                event.serial(out)
+            }
+            CustomEvent::Revealed(event) => {
+                out.write_u8(REVEALED_EVENT_TAG)?;
+                event.serial(out)
+            }
+            CustomEvent::AdminTransferProposed(event) => {
+                out.write_u8(ADMIN_TRANSFER_PROPOSED_EVENT_TAG)?;
+                event.serial(out)
+            }
+            CustomEvent::AdminChanged(event) => {
+                out.write_u8(ADMIN_CHANGED_EVENT_TAG)?;
+                event.serial(out)
+            }
+            CustomEvent::Approval(event) => {
+                out.write_u8(APPROVAL_EVENT_TAG)?;
+                event.serial(out)
+            }
+            CustomEvent::Deposit(event) => {
+                out.write_u8(DEPOSIT_EVENT_TAG)?;
+                event.serial(out)
+            }
+            CustomEvent::Withdraw(event) => {
+                out.write_u8(WITHDRAW_EVENT_TAG)?;
+                event.serial(out)
+            }
+            CustomEvent::Forward(event) => {
+                out.write_u8(FORWARD_EVENT_TAG)?;
+                event.serial(out)
+            }
+            CustomEvent::Nonce(event) => {
+                out.write_u8(NONCE_EVENT_TAG)?;
+                event.serial(out)
+            }
+        }
+    }
 }
 
 type ContractError = Cis2Error<CustomContractError>;
@@ -192,45 +1251,549 @@ impl From<NewContractNameError> for CustomContractError {
     }
 }
 
+/// Mapping errors related to contract upgrades to CustomContractError.
+impl From<UpgradeError> for CustomContractError {
+    fn from(ue: UpgradeError) -> Self {
+        match ue {
+            UpgradeError::MissingModule => Self::FailedUpgradeMissingModule,
+            UpgradeError::MissingContract => Self::FailedUpgradeMissingContract,
+            UpgradeError::UnsupportedModuleVersion => Self::FailedUpgradeUnsupportedModuleVersion,
+        }
+    }
+}
+
 impl<S: HasStateApi> State<S> {
-    /// Construct a state with no tokens
-    fn empty(state_builder: &mut StateBuilder<S>) -> Self {
+    /// Construct a state with no tokens for the given collection and admin,
+    /// forwarding payments to the given treasury account.
+    fn empty(
+        state_builder: &mut StateBuilder<S>,
+        collection: CollectionInfo,
+        admin: Address,
+        treasury: AccountAddress,
+        base_url: Option<String>,
+    ) -> Self {
         State {
+            version: STATE_VERSION,
+            collection,
+            admin,
+            pending_admin: None,
             state: state_builder.new_map(),
             tokens: state_builder.new_map(),
+            holders: state_builder.new_map(),
             implementors: state_builder.new_map(),
+            roles: state_builder.new_set(),
+            metadata_frozen: false,
+            paused: false,
+            nonces_registry: state_builder.new_map(),
+            public_keys: state_builder.new_map(),
+            drops: state_builder.new_map(),
+            treasury,
+            redeemed_vouchers: state_builder.new_set(),
+            base_url,
+            placeholder: None,
+            reveal_commitment: None,
+            revealed: false,
+            royalties: Vec::new(),
+            receive_mode: ReceiveMode::Reject,
+            deposits: state_builder.new_map(),
         }
     }
 
-    /// Mints an amount of tokens with a given address as the owner.
-    fn mint(
-        &mut self,
-        token_id: &ContractTokenId,
-        token_metadata: &TokenMetadata,
-        amount: ContractTokenAmount,
-        owner: &Address,
-        state_builder: &mut StateBuilder<S>,
-    ) {
-        self.tokens
-            .insert(*token_id, token_metadata.to_metadata_url());
-        let mut owner_state = self
-            .state
-            .entry(*owner)
-            .or_insert_with(|| AddressState::empty(state_builder));
-        let mut owner_balance = owner_state.balances.entry(*token_id).or_insert(0.into());
-        *owner_balance += amount;
+    /// Check if an address has been granted a given role.
+    /// The `Admin` role is held by the admin of the contract only.
+    fn has_role(&self, address: &Address, role: Role) -> bool {
+        match role {
+            Role::Admin => self.admin == *address,
+            _ => self.roles.contains(&(*address, role)),
+        }
     }
 
-    /// Check that the token ID currently exists in this contract.
-    #[inline(always)]
-    fn contains_token(&self, token_id: &ContractTokenId) -> bool {
-        self.tokens.get(token_id).is_some()
+    /// Grant a role to an address.
+    /// Succeeds even if the address already has the role.
+    fn grant_role(&mut self, address: &Address, role: Role) {
+        self.roles.insert((*address, role));
     }
 
-    /// Get the current balance of a given token id for a given address.
-    /// Results in an error if the token id does not exist in the state.
-    fn balance(
-        &self,
+    /// Revoke a role from an address.
+    /// Succeeds even if the address does not have the role.
+    fn revoke_role(&mut self, address: &Address, role: Role) {
+        self.roles.remove(&(*address, role));
+    }
+
+    /// Replace the admin with the pending admin, which moves the `Admin` role.
+    /// Returns the previous admin.
+    /// Results in an error if the given address is not the pending admin.
+    fn accept_admin(&mut self, address: &Address) -> ContractResult<(Address, Vec<Role>)> {
+        ensure!(
+            self.pending_admin == Some(*address),
+            ContractError::Unauthorized
+        );
+        let previous_admin = self.admin;
+        let moved_roles: Vec<Role> = ADMIN_ROLES
+            .iter()
+            .copied()
+            .filter(|role| self.has_role(&previous_admin, *role))
+            .collect();
+        for role in moved_roles.iter() {
+            self.revoke_role(&previous_admin, *role);
+            self.grant_role(address, *role);
+        }
+        self.admin = *address;
+        self.pending_admin = None;
+        Ok((previous_admin, moved_roles))
+    }
+
+    /// Adds a new token type with no supply.
+    /// The metadata URL is derived from the base URL if no metadata is given.
+    /// Overwrites the token type if it already exists.
+    fn create_token(
+        &mut self,
+        token_id: &ContractTokenId,
+        token_metadata: Option<&TokenMetadata>,
+        max_supply: ContractTokenAmount,
+        soulbound: bool,
+        royalties: Option<Vec<Royalty>>,
+    ) {
+        let _ = self.tokens.insert(
+            token_id.to_owned(),
+            TokenState {
+                metadata_url: token_metadata.map(TokenMetadata::to_metadata_url),
+                supply: 0.into(),
+                max_supply,
+                metadata_frozen: false,
+                soulbound,
+                royalties,
+            },
+        );
+    }
+
+    /// Get the royalties of a token type, which are the royalties of the
+    /// collection unless the token type has royalties of its own.
+    /// Results in an error if the token id does not exist in the state.
+    fn royalties(&self, token_id: &ContractTokenId) -> ContractResult<Vec<Royalty>> {
+        let token_state = self
+            .tokens
+            .get(token_id)
+            .ok_or(ContractError::InvalidTokenId)?;
+        Ok(token_state
+            .royalties
+            .clone()
+            .unwrap_or_else(|| self.royalties.clone()))
+    }
+
+    /// Get the amount of CIS2 tokens kept in custody for a depositor.
+    fn deposit_of(&self, deposit: &DepositKey) -> ContractTokenAmount {
+        self.deposits
+            .get(deposit)
+            .map(|amount| *amount)
+            .unwrap_or_else(|| 0.into())
+    }
+
+    /// Update the state adding CIS2 tokens kept in custody for a depositor.
+    fn deposit(&mut self, deposit: DepositKey, amount: ContractTokenAmount) {
+        let mut deposited = self.deposits.entry(deposit).or_insert(0.into());
+        *deposited += amount;
+    }
+
+    /// Update the state removing CIS2 tokens kept in custody for a depositor.
+    /// Results in an error if the depositor has insufficient tokens in
+    /// custody.
+    fn withdraw(
+        &mut self,
+        deposit: &DepositKey,
+        amount: ContractTokenAmount,
+    ) -> ContractResult<()> {
+        let remaining = {
+            let mut deposited = self
+                .deposits
+                .entry(deposit.clone())
+                .occupied_or(ContractError::InsufficientFunds)?;
+            ensure!(*deposited >= amount, ContractError::InsufficientFunds);
+            *deposited -= amount;
+            *deposited
+        };
+        if remaining == 0.into() {
+            self.deposits.remove(deposit);
+        }
+        Ok(())
+    }
+
+    /// Check whether the tokens of a token type are non-transferable.
+    /// Results in an error if the token id does not exist in the state.
+    fn is_soulbound(&self, token_id: &ContractTokenId) -> ContractResult<bool> {
+        self.tokens
+            .get(token_id)
+            .map(|token_state| token_state.soulbound)
+            .ok_or(ContractError::InvalidTokenId)
+    }
+
+    /// Update the metadata URL of a token type.
+    /// Results in an error if the token id does not exist in the state or if
+    /// the metadata of the token type is frozen.
+    fn update_metadata(
+        &mut self,
+        token_id: &ContractTokenId,
+        token_metadata: &TokenMetadata,
+    ) -> ContractResult<()> {
+        ensure!(
+            !self.metadata_frozen,
+            ContractError::Custom(CustomContractError::MetadataFrozen)
+        );
+        let mut token_state = self
+            .tokens
+            .entry(token_id.to_owned())
+            .occupied_or(ContractError::InvalidTokenId)?;
+        ensure!(
+            !token_state.metadata_frozen,
+            ContractError::Custom(CustomContractError::MetadataFrozen)
+        );
+        token_state.metadata_url = Some(token_metadata.to_metadata_url());
+        Ok(())
+    }
+
+    /// Permanently lock the metadata URL of a token type.
+    /// A metadata URL derived from the base URL is stored as the explicit
+    /// metadata URL of the token type, such that it is not affected by later
+    /// changes to the base URL.
+    /// Results in an error if the token id does not exist in the state, if
+    /// it has no metadata URL or if the metadata is hidden until the reveal.
+    fn freeze_token_metadata(&mut self, token_id: &ContractTokenId) -> ContractResult<()> {
+        ensure!(
+            !self.is_hidden(),
+            ContractError::Custom(CustomContractError::NotRevealed)
+        );
+        let metadata_url = self.metadata_url(token_id)?;
+        let mut token_state = self
+            .tokens
+            .entry(token_id.to_owned())
+            .occupied_or(ContractError::InvalidTokenId)?;
+        token_state.metadata_url = Some(metadata_url);
+        token_state.metadata_frozen = true;
+        Ok(())
+    }
+
+    /// Set the base URL the metadata URLs of the token types are derived
+    /// from.
+    /// Results in an error if the metadata of every token type is frozen or if
+    /// a reveal has been committed to, in which case the base URL is only set
+    /// by the reveal.
+    fn set_base_url(&mut self, base_url: String) -> ContractResult<()> {
+        ensure!(
+            !self.metadata_frozen,
+            ContractError::Custom(CustomContractError::MetadataFrozen)
+        );
+        ensure!(
+            self.reveal_commitment.is_none(),
+            ContractError::Custom(CustomContractError::RevealAlreadyCommitted)
+        );
+        self.base_url = Some(base_url);
+        Ok(())
+    }
+
+    /// Check whether the derived metadata URLs are hidden behind the
+    /// placeholder, which is the case after committing to a reveal until the
+    /// reveal.
+    fn is_hidden(&self) -> bool {
+        self.reveal_commitment.is_some() && !self.revealed
+    }
+
+    /// Hide the derived metadata URLs behind a placeholder until the reveal
+    /// matching the commitment.
+    /// Results in an error if a reveal has already been committed to.
+    fn commit_reveal(
+        &mut self,
+        placeholder: MetadataUrl,
+        commitment: [u8; 32],
+    ) -> ContractResult<()> {
+        ensure!(
+            !self.metadata_frozen,
+            ContractError::Custom(CustomContractError::MetadataFrozen)
+        );
+        ensure!(
+            self.reveal_commitment.is_none(),
+            ContractError::Custom(CustomContractError::RevealAlreadyCommitted)
+        );
+        self.placeholder = Some(placeholder);
+        self.reveal_commitment = Some(commitment);
+        Ok(())
+    }
+
+    /// Reveal the derived metadata URLs by setting the final base URL, given
+    /// the hash of the serialized reveal parameters.
+    /// Results in an error if no reveal has been committed to, if the reveal
+    /// has already happened, if the hash does not match the commitment or if
+    /// the metadata of every token type is frozen.
+    fn reveal(&mut self, base_url: String, reveal_hash: [u8; 32]) -> ContractResult<()> {
+        let commitment = self.reveal_commitment.ok_or(ContractError::Custom(
+            CustomContractError::RevealNotCommitted,
+        ))?;
+        ensure!(
+            !self.revealed,
+            ContractError::Custom(CustomContractError::AlreadyRevealed)
+        );
+        ensure!(
+            reveal_hash == commitment,
+            ContractError::Custom(CustomContractError::InvalidReveal)
+        );
+        ensure!(
+            !self.metadata_frozen,
+            ContractError::Custom(CustomContractError::MetadataFrozen)
+        );
+        self.base_url = Some(base_url);
+        self.revealed = true;
+        self.placeholder = None;
+        Ok(())
+    }
+
+    /// Get the metadata URL of a given token id, which is either the explicit
+    /// metadata URL of the token type or derived from the base URL. The
+    /// derived metadata URL is the placeholder until the reveal.
+    /// Results in an error if the token id does not exist in the state or if
+    /// it has no explicit metadata URL and no base URL is set.
+    fn metadata_url(&self, token_id: &ContractTokenId) -> ContractResult<MetadataUrl> {
+        let token_state = self
+            .tokens
+            .get(token_id)
+            .ok_or(ContractError::InvalidTokenId)?;
+        if let Some(metadata_url) = &token_state.metadata_url {
+            return Ok(metadata_url.clone());
+        }
+        if self.is_hidden() {
+            if let Some(placeholder) = &self.placeholder {
+                return Ok(placeholder.clone());
+            }
+        }
+        let base_url = self.base_url.as_ref().ok_or(ContractError::Custom(
+            CustomContractError::MissingMetadataUrl,
+        ))?;
+        Ok(MetadataUrl {
+            url: derive_token_url(base_url, token_id),
+            hash: None,
+        })
+    }
+
+    /// Mints an amount of tokens with a given address as the owner.
+    /// Results in an error if the token id does not exist in the state or if
+    /// the supply would exceed the maximum supply of the token type.
+    fn mint(
+        &mut self,
+        token_id: &ContractTokenId,
+        amount: ContractTokenAmount,
+        owner: &Address,
+        state_builder: &mut StateBuilder<S>,
+    ) -> ContractResult<()> {
+        {
+            let mut token_state = self
+                .tokens
+                .entry(token_id.to_owned())
+                .occupied_or(ContractError::InvalidTokenId)?;
+            ensure!(
+                amount <= token_state.max_supply - token_state.supply,
+                ContractError::Custom(CustomContractError::MaxSupplyExceeded)
+            );
+            token_state.supply += amount;
+        }
+        if amount == 0.into() {
+            return Ok(());
+        }
+        {
+            let mut owner_state = self
+                .state
+                .entry(*owner)
+                .or_insert_with(|| AddressState::empty(state_builder));
+            let mut owner_balance = owner_state
+                .balances
+                .entry(token_id.to_owned())
+                .or_insert(0.into());
+            *owner_balance += amount;
+        }
+        self.add_holder(token_id, owner, state_builder);
+        Ok(())
+    }
+
+    /// Burns an amount of tokens owned by a given address.
+    /// Results in an error if the token id does not exist in the state or if
+    /// the owner have insufficient tokens to burn.
+    /// The token type is kept once its total supply is zero, so minting the
+    /// token id again cannot reset its maximum supply, frozen metadata,
+    /// soulbound flag or royalties.
+    fn burn(
+        &mut self,
+        token_id: &ContractTokenId,
+        amount: ContractTokenAmount,
+        owner: &Address,
+    ) -> ContractResult<()> {
+        ensure!(self.contains_token(token_id), ContractError::InvalidTokenId);
+        // A zero burn does not modify the state.
+        if amount == 0.into() {
+            return Ok(());
+        }
+
+        let remaining = {
+            let mut owner_state = self
+                .state
+                .entry(*owner)
+                .occupied_or(ContractError::InsufficientFunds)?;
+            let remaining = {
+                let mut owner_balance = owner_state
+                    .balances
+                    .entry(token_id.to_owned())
+                    .occupied_or(ContractError::InsufficientFunds)?;
+                ensure!(*owner_balance >= amount, ContractError::InsufficientFunds);
+                *owner_balance -= amount;
+                *owner_balance
+            };
+            if remaining == 0.into() {
+                owner_state.balances.remove(token_id);
+            }
+            remaining
+        };
+        if remaining == 0.into() {
+            self.remove_holder(token_id, owner);
+        }
+
+        let supply = {
+            let mut token_state = self
+                .tokens
+                .entry(token_id.to_owned())
+                .occupied_or(ContractError::InvalidTokenId)?;
+            token_state.supply -= amount;
+            token_state.supply
+        };
+        if supply == 0.into() {
+            self.holders.remove(token_id);
+        }
+
+        Ok(())
+    }
+
+    /// Record an address as a holder of a given token id.
+    /// Succeeds even if the address is already recorded.
+    fn add_holder(
+        &mut self,
+        token_id: &ContractTokenId,
+        address: &Address,
+        state_builder: &mut StateBuilder<S>,
+    ) {
+        let mut holders = self
+            .holders
+            .entry(token_id.to_owned())
+            .or_insert_with(|| state_builder.new_set());
+        holders.insert(*address);
+    }
+
+    /// Remove an address from the holders of a given token id.
+    /// Succeeds even if the address is not recorded.
+    fn remove_holder(&mut self, token_id: &ContractTokenId, address: &Address) {
+        self.holders
+            .entry(token_id.to_owned())
+            .and_modify(|holders| {
+                holders.remove(address);
+            });
+    }
+
+    /// Configure the drop of a given token id.
+    /// The amounts already minted are kept if the drop is reconfigured.
+    fn set_drop(
+        &mut self,
+        token_id: &ContractTokenId,
+        config: DropConfig,
+        state_builder: &mut StateBuilder<S>,
+    ) {
+        let mut drop = self
+            .drops
+            .entry(token_id.to_owned())
+            .or_insert_with(|| DropState::new(config.clone(), state_builder));
+        drop.config = config;
+    }
+
+    /// Record an amount of tokens minted by an account in the drop of a given
+    /// token id and return the price of the tokens.
+    /// Results in an error if the drop does not exist, is not open at the
+    /// given time or if the amount exceeds the limits of the drop.
+    fn record_drop_mint(
+        &mut self,
+        token_id: &ContractTokenId,
+        amount: ContractTokenAmount,
+        account: &AccountAddress,
+        now: Timestamp,
+    ) -> ContractResult<Amount> {
+        let mut drop = self
+            .drops
+            .entry(token_id.to_owned())
+            .occupied_or(ContractError::Custom(CustomContractError::DropNotFound))?;
+        let max_per_wallet = drop.config.max_per_wallet;
+        {
+            let mut minted_by = drop.minted_by.entry(*account).or_insert(0.into());
+            ensure!(
+                *minted_by <= max_per_wallet && amount <= max_per_wallet - *minted_by,
+                ContractError::Custom(CustomContractError::WalletLimitExceeded)
+            );
+            *minted_by += amount;
+        }
+        drop.record_mint(amount, now)
+    }
+
+    /// Record an amount of tokens claimed by an account through the allowlist
+    /// of the drop of a given token id and return the price of the tokens.
+    /// The entry of the account in the allowlist is checked using the `proof`.
+    /// Results in an error if the drop does not exist, has no allowlist, if
+    /// the proof is invalid, if the amount exceeds the allowance of the
+    /// account, if the drop is not open at the given time or if the amount
+    /// exceeds the limits of the drop.
+    #[allow(clippy::too_many_arguments)]
+    fn record_allowlist_mint(
+        &mut self,
+        token_id: &ContractTokenId,
+        amount: ContractTokenAmount,
+        account: &AccountAddress,
+        allowance: ContractTokenAmount,
+        proof: &[MerkleHash],
+        now: Timestamp,
+        crypto_primitives: &impl HasCryptoPrimitives,
+    ) -> ContractResult<Amount> {
+        let mut drop = self
+            .drops
+            .entry(token_id.to_owned())
+            .occupied_or(ContractError::Custom(CustomContractError::DropNotFound))?;
+        let root = drop
+            .allowlist_root
+            .ok_or(ContractError::Custom(CustomContractError::AllowlistNotSet))?;
+        let leaf = merkle::hash_leaf(crypto_primitives, account, allowance);
+        ensure!(
+            merkle::verify(crypto_primitives, &root, leaf, proof),
+            ContractError::Custom(CustomContractError::InvalidProof)
+        );
+        {
+            let mut claimed = drop.claimed.entry(*account).or_insert(0.into());
+            ensure!(
+                *claimed <= allowance && amount <= allowance - *claimed,
+                ContractError::Custom(CustomContractError::AllowanceExceeded)
+            );
+            *claimed += amount;
+        }
+        drop.record_mint(amount, now)
+    }
+
+    /// Check that the token ID currently exists in this contract.
+    #[inline(always)]
+    fn contains_token(&self, token_id: &ContractTokenId) -> bool {
+        self.tokens.get(token_id).is_some()
+    }
+
+    /// Get the total supply of a given token id.
+    /// Results in an error if the token id does not exist in the state.
+    fn total_supply(&self, token_id: &ContractTokenId) -> ContractResult<ContractTokenAmount> {
+        self.tokens
+            .get(token_id)
+            .map(|token_state| token_state.supply)
+            .ok_or(ContractError::InvalidTokenId)
+    }
+
+    /// Get the current balance of a given token id for a given address.
+    /// Results in an error if the token id does not exist in the state.
+    fn balance(
+        &self,
         token_id: &ContractTokenId,
         address: &Address,
     ) -> ContractResult<ContractTokenAmount> {
@@ -244,17 +1807,102 @@ impl<S: HasStateApi> State<S> {
         Ok(balance)
     }
 
-    /// Check if an address is an operator of a given owner address.
-    fn is_operator(&self, address: &Address, owner: &Address) -> bool {
+    /// Check if an address is an operator of a given owner address at the
+    /// given time. An expired operator is not an operator.
+    fn is_operator(&self, address: &Address, owner: &Address, now: Timestamp) -> bool {
         self.state
             .get(owner)
-            .map(|address_state| address_state.operators.contains(address))
+            .and_then(|address_state| {
+                address_state
+                    .operators
+                    .get(address)
+                    .map(|expiry| match *expiry {
+                        Some(expiry) => now < expiry,
+                        None => true,
+                    })
+            })
             .unwrap_or(false)
     }
 
+    /// Get the amount of tokens of a token type a spender can transfer on
+    /// behalf of a given owner.
+    fn allowance(
+        &self,
+        owner: &Address,
+        spender: &Address,
+        token_id: &ContractTokenId,
+    ) -> ContractTokenAmount {
+        self.state
+            .get(owner)
+            .and_then(|address_state| {
+                address_state
+                    .allowances
+                    .get(&(*spender, token_id.to_owned()))
+                    .map(|amount| *amount)
+            })
+            .unwrap_or_else(|| 0.into())
+    }
+
+    /// Update the state setting the allowance of a spender for the tokens of a
+    /// given owner. An allowance of zero is removed.
+    fn set_allowance(
+        &mut self,
+        owner: &Address,
+        spender: &Address,
+        token_id: &ContractTokenId,
+        amount: ContractTokenAmount,
+        state_builder: &mut StateBuilder<S>,
+    ) {
+        let key = (*spender, token_id.to_owned());
+        if amount == 0.into() {
+            self.state
+                .entry(*owner)
+                .and_modify(|owner_state| owner_state.allowances.remove(&key));
+        } else {
+            let mut owner_state = self
+                .state
+                .entry(*owner)
+                .or_insert_with(|| AddressState::empty(state_builder));
+            let _ = owner_state.allowances.insert(key, amount);
+        }
+    }
+
+    /// Update the state deducting an amount from the allowance of a spender
+    /// for the tokens of a given owner. The allowance is removed once it
+    /// reaches zero.
+    /// Results in an error if the spender has no allowance or an insufficient
+    /// allowance, even for a zero amount.
+    fn spend_allowance(
+        &mut self,
+        owner: &Address,
+        spender: &Address,
+        token_id: &ContractTokenId,
+        amount: ContractTokenAmount,
+    ) -> ContractResult<()> {
+        let mut owner_state = self
+            .state
+            .entry(*owner)
+            .occupied_or(ContractError::Unauthorized)?;
+        let key = (*spender, token_id.to_owned());
+        let remaining = {
+            let mut allowance = owner_state
+                .allowances
+                .entry(key.to_owned())
+                .occupied_or(ContractError::Unauthorized)?;
+            ensure!(*allowance >= amount, ContractError::Unauthorized);
+            *allowance -= amount;
+            *allowance
+        };
+        if remaining == 0.into() {
+            owner_state.allowances.remove(&key);
+        }
+        Ok(())
+    }
+
     /// Update the state with a transfer.
-    /// Results in an error if the token id does not exist in the state or if
-    /// the from address have insufficient tokens to do the transfer.
+    /// Results in an error if the token id does not exist in the state, if the
+    /// token type is soulbound or if the from address have insufficient tokens
+    /// to do the transfer.
     fn transfer(
         &mut self,
         token_id: &ContractTokenId,
@@ -263,7 +1911,11 @@ impl<S: HasStateApi> State<S> {
         to: &Address,
         state_builder: &mut StateBuilder<S>,
     ) -> ContractResult<()> {
-        ensure!(self.contains_token(token_id), ContractError::InvalidTokenId);
+        // Soulbound tokens are rejected even for a zero transfer.
+        ensure!(
+            !self.is_soulbound(token_id)?,
+            ContractError::Custom(CustomContractError::Soulbound)
+        );
         // A zero transfer does not modify the state.
         if amount == 0.into() {
             return Ok(());
@@ -272,46 +1924,63 @@ impl<S: HasStateApi> State<S> {
         // Get the `from` state and balance, if not present it will fail since the
         // balance is interpreted as 0 and the transfer amount must be more than
         // 0 as this point.;
-        {
+        let remaining = {
             let mut from_address_state = self
                 .state
                 .entry(*from)
                 .occupied_or(ContractError::InsufficientFunds)?;
-            let mut from_balance = from_address_state
-                .balances
-                .entry(*token_id)
-                .occupied_or(ContractError::InsufficientFunds)?;
-            ensure!(*from_balance >= amount, ContractError::InsufficientFunds);
-            *from_balance -= amount;
+            let remaining = {
+                let mut from_balance = from_address_state
+                    .balances
+                    .entry(token_id.to_owned())
+                    .occupied_or(ContractError::InsufficientFunds)?;
+                ensure!(*from_balance >= amount, ContractError::InsufficientFunds);
+                *from_balance -= amount;
+                *from_balance
+            };
+            // Remove the balance entirely, such that only tokens held by the
+            // address are listed by `tokensOf`.
+            if remaining == 0.into() {
+                from_address_state.balances.remove(token_id);
+            }
+            remaining
+        };
+        if remaining == 0.into() {
+            self.remove_holder(token_id, from);
         }
 
-        let mut to_address_state = self
-            .state
-            .entry(*to)
-            .or_insert_with(|| AddressState::empty(state_builder));
-        let mut to_address_balance = to_address_state
-            .balances
-            .entry(*token_id)
-            .or_insert(0.into());
-        *to_address_balance += amount;
+        {
+            let mut to_address_state = self
+                .state
+                .entry(*to)
+                .or_insert_with(|| AddressState::empty(state_builder));
+            let mut to_address_balance = to_address_state
+                .balances
+                .entry(token_id.to_owned())
+                .or_insert(0.into());
+            *to_address_balance += amount;
+        }
+        self.add_holder(token_id, to, state_builder);
 
         Ok(())
     }
 
-    /// Update the state adding a new operator for a given address.
+    /// Update the state adding a new operator for a given address, which
+    /// expires at the given time, if any.
     /// Succeeds even if the `operator` is already an operator for the
-    /// `address`.
+    /// `address`, replacing its expiry.
     fn add_operator(
         &mut self,
         owner: &Address,
         operator: &Address,
+        expiry: Option<Timestamp>,
         state_builder: &mut StateBuilder<S>,
     ) {
         let mut owner_state = self
             .state
             .entry(*owner)
             .or_insert_with(|| AddressState::empty(state_builder));
-        owner_state.operators.insert(*operator);
+        let _ = owner_state.operators.insert(*operator, expiry);
     }
 
     /// Update the state removing an operator for a given address.
@@ -337,28 +2006,184 @@ impl<S: HasStateApi> State<S> {
         std_id: StandardIdentifierOwned,
         implementors: Vec<ContractAddress>,
     ) {
-        self.implementors.insert(std_id, implementors);
+        let _ = self.implementors.insert(std_id, implementors);
     }
 }
 
-// Contract functions
-/// Initialize contract instance with a no token types.
-#[init(contract = "CIS2-Multi")]
-fn contract_init<S: HasStateApi>(
-    _ctx: &impl HasInitContext,
-    state_builder: &mut StateBuilder<S>,
-) -> InitResult<State<S>> {
-    // Construct the initial contract state.
-    Ok(State::empty(state_builder))
+impl<S: HasStateApi> StateV1<S> {
+    /// Migrate the state to the current state version. Operators are kept
+    /// without expiry, token types are not soulbound and use the royalties of
+    /// the collection, which has no royalties and rejects received tokens.
+    /// The `Admin` role entries are dropped, since the role is derived from
+    /// the admin.
+    ///
+    /// The maps and sets cannot be modified while iterating over them, hence
+    /// their entries are collected first.
+    fn migrate(mut self, state_builder: &mut StateBuilder<S>) -> State<S> {
+        let addresses: Vec<Address> = self.state.iter().map(|(address, _)| *address).collect();
+        let mut address_states = state_builder.new_map();
+        for address in addresses {
+            // Unwrapping is safe, since the address was just read from the map.
+            let mut address_state = self.state.remove_and_get(&address).unwrap_abort();
+            let mut operators = state_builder.new_map();
+            for operator in address_state.operators.iter() {
+                let _ = operators.insert(*operator, None);
+            }
+            address_state.operators.clear();
+            let _ = address_states.insert(
+                address,
+                AddressState {
+                    balances: address_state.balances,
+                    operators,
+                    allowances: state_builder.new_map(),
+                },
+            );
+        }
+
+        let token_types: Vec<(ContractTokenId, TokenStateV1)> = self
+            .tokens
+            .iter()
+            .map(|(token_id, token_state)| (token_id.to_owned(), token_state.clone()))
+            .collect();
+        self.tokens.clear_flat();
+        let mut tokens = state_builder.new_map();
+        for (token_id, token_state) in token_types {
+            let _ = tokens.insert(
+                token_id,
+                TokenState {
+                    metadata_url: token_state.metadata_url,
+                    supply: token_state.supply,
+                    max_supply: token_state.max_supply,
+                    metadata_frozen: token_state.metadata_frozen,
+                    soulbound: false,
+                    royalties: None,
+                },
+            );
+        }
+
+        let admins: Vec<Address> = self
+            .roles
+            .iter()
+            .filter_map(|entry| match *entry {
+                (address, Role::Admin) => Some(address),
+                _ => None,
+            })
+            .collect();
+        for address in admins {
+            self.roles.remove(&(address, Role::Admin));
+        }
+
+        State {
+            version: STATE_VERSION,
+            collection: self.collection,
+            admin: self.admin,
+            pending_admin: self.pending_admin,
+            state: address_states,
+            tokens,
+            holders: self.holders,
+            implementors: self.implementors,
+            roles: self.roles,
+            metadata_frozen: self.metadata_frozen,
+            paused: self.paused,
+            nonces_registry: self.nonces_registry,
+            public_keys: self.public_keys,
+            drops: self.drops,
+            treasury: self.treasury,
+            redeemed_vouchers: self.redeemed_vouchers,
+            base_url: self.base_url,
+            placeholder: self.placeholder,
+            reveal_commitment: self.reveal_commitment,
+            revealed: self.revealed,
+            royalties: Vec::new(),
+            receive_mode: ReceiveMode::Reject,
+            deposits: state_builder.new_map(),
+        }
+    }
 }
 
-#[derive(Serialize, SchemaType)]
-struct ViewAddressState {
-    balances: Vec<(ContractTokenId, ContractTokenAmount)>,
-    operators: Vec<Address>,
+/// Derive the metadata URL of a token type from a base URL, by replacing
+/// every occurrence of `{id}` with the token ID encoded in hex, or appending
+/// the token ID if the base URL does not contain `{id}`.
+/// The token ID is encoded as the bytes of its CIS2 serialization, without the
+/// leading length byte.
+fn derive_token_url(base_url: &str, token_id: &ContractTokenId) -> String {
+    let token_id_hex = hex::encode(&to_bytes(token_id)[1..]);
+    if base_url.contains("{id}") {
+        base_url.replace("{id}", &token_id_hex)
+    } else {
+        let mut url = base_url.to_string();
+        url.push_str(&token_id_hex);
+        url
+    }
 }
 
-#[derive(Serialize, SchemaType)]
+// Contract functions
+/// Initialize contract instance with a no token types for a collection with
+/// the given name, symbol and metadata, and an optional base URL for the
+/// metadata of the token types.
+/// The admin, or else the account instantiating the contract, is granted every
+/// role. The admin, if it is an account, or else the account instantiating the
+/// contract is the initial treasury.
+///
+/// It rejects if:
+/// - Fails to parse parameter.
+/// - The hash of the collection metadata is invalid.
+#[init(contract = "CIS2-Multi", parameter = "InitParams")]
+fn contract_init<S: HasStateApi>(
+    ctx: &impl HasInitContext,
+    state_builder: &mut StateBuilder<S>,
+) -> InitResult<State<S>> {
+    // Parse the parameter.
+    let params: InitParams = ctx.parameter_cursor().get()?;
+    if let Some(metadata) = &params.metadata {
+        metadata.ensure_valid_hash()?;
+    }
+    let collection = CollectionInfo {
+        name: params.name,
+        symbol: params.symbol,
+        metadata_url: params.metadata.as_ref().map(TokenMetadata::to_metadata_url),
+    };
+    let admin = params
+        .admin
+        .unwrap_or_else(|| Address::Account(ctx.init_origin()));
+    let treasury = match admin {
+        Address::Account(account) => account,
+        Address::Contract(_) => ctx.init_origin(),
+    };
+    // Construct the initial contract state.
+    ensure_valid_royalties(&params.royalties)?;
+    let mut state = State::empty(state_builder, collection, admin, treasury, params.base_url);
+    state.royalties = params.royalties;
+    state.receive_mode = params.receive_mode;
+    for role in ADMIN_ROLES {
+        state.grant_role(&admin, role);
+    }
+    Ok(state)
+}
+
+/// Get the name, symbol and metadata URL of the collection.
+#[receive(
+    contract = "CIS2-Multi",
+    name = "collectionInfo",
+    return_value = "CollectionInfo",
+    error = "ContractError"
+)]
+fn contract_collection_info<S: HasStateApi>(
+    _ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<CollectionInfo> {
+    Ok(host.state().collection.clone())
+}
+
+#[cfg(feature = "full-view")]
+#[derive(Serialize, SchemaType)]
+struct ViewAddressState {
+    balances: Vec<(ContractTokenId, ContractTokenAmount)>,
+    operators: Vec<(Address, Option<Timestamp>)>,
+}
+
+#[cfg(feature = "full-view")]
+#[derive(Serialize, SchemaType)]
 struct ViewState {
     state: Vec<(Address, ViewAddressState)>,
     tokens: Vec<ContractTokenId>,
@@ -367,6 +2192,9 @@ struct ViewState {
 /// View function for testing. This reports on the entire state of the contract
 /// for testing purposes. In a realistic example there `balance_of` and similar
 /// functions with a smaller response.
+/// Only available with the `full-view` feature, use the paginated views
+/// `viewTokens`, `holdersOf` and `tokensOf` instead.
+#[cfg(feature = "full-view")]
 #[receive(contract = "CIS2-Multi", name = "view", return_value = "ViewState")]
 fn contract_view<S: HasStateApi>(
     _ctx: &impl HasReceiveContext,
@@ -379,10 +2207,10 @@ fn contract_view<S: HasStateApi>(
         let mut balances = Vec::new();
         let mut operators = Vec::new();
         for (token_id, amount) in a_state.balances.iter() {
-            balances.push((*token_id, *amount));
+            balances.push((token_id.to_owned(), *amount));
         }
-        for o in a_state.operators.iter() {
-            operators.push(*o);
+        for (o, expiry) in a_state.operators.iter() {
+            operators.push((*o, *expiry));
         }
 
         inner_state.push((
@@ -404,16 +2232,268 @@ fn contract_view<S: HasStateApi>(
     })
 }
 
+/// Collect a page of at most `limit` items, capped at `MAX_PAGE_LIMIT`,
+/// following the item with the key `cursor`. The items must be ordered by the
+/// serialization of their keys, as is the case when iterating a `StateMap`.
+/// Returns the page of items and the cursor for the next page, which is `None`
+/// if there are no more items.
+///
+/// Note: The state can only be iterated from the first item of a map, so the
+/// items before the cursor are still visited, although not collected.
+fn paginate<K: Serial + Clone, V>(
+    items: impl Iterator<Item = (K, V)>,
+    cursor: Option<K>,
+    limit: u32,
+) -> (Vec<(K, V)>, Option<K>) {
+    let cursor_bytes = cursor.map(|key| to_bytes(&key));
+    let mut items = items.skip_while(|(key, _)| match &cursor_bytes {
+        Some(cursor_bytes) => to_bytes(key) <= *cursor_bytes,
+        None => false,
+    });
+    let limit = limit.min(MAX_PAGE_LIMIT);
+    let page: Vec<(K, V)> = items.by_ref().take(limit as usize).collect();
+    let next_cursor = if items.next().is_some() {
+        page.last().map(|(key, _)| key.clone())
+    } else {
+        None
+    };
+    (page, next_cursor)
+}
+
+/// The parameter type for the contract function `viewTokens`.
+#[derive(Serialize, SchemaType)]
+struct ViewTokensParams {
+    /// The token ID to continue after, `None` to start from the first token.
+    cursor: Option<ContractTokenId>,
+    /// The maximum number of tokens to return, at most `MAX_PAGE_LIMIT`.
+    limit: u32,
+}
+
+/// The response type for the contract function `viewTokens`.
+#[derive(Debug, Serialize, SchemaType)]
+struct ViewTokensResponse {
+    /// The token IDs and their total supply.
+    #[concordium(size_length = 2)]
+    tokens: Vec<(ContractTokenId, ContractTokenAmount)>,
+    /// The cursor for the next page, `None` if there are no more tokens.
+    cursor: Option<ContractTokenId>,
+}
+
+/// The parameter type for the contract function `holdersOf`.
+#[derive(Serialize, SchemaType)]
+struct HoldersOfParams {
+    /// The token ID to list the holders of.
+    token_id: ContractTokenId,
+    /// The address to continue after, `None` to start from the first holder.
+    cursor: Option<Address>,
+    /// The maximum number of holders to return, at most `MAX_PAGE_LIMIT`.
+    limit: u32,
+}
+
+/// The response type for the contract function `holdersOf`.
+#[derive(Debug, Serialize, SchemaType)]
+struct HoldersOfResponse {
+    /// The holders of the token and their balance.
+    #[concordium(size_length = 2)]
+    holders: Vec<(Address, ContractTokenAmount)>,
+    /// The cursor for the next page, `None` if there are no more holders.
+    cursor: Option<Address>,
+}
+
+/// The parameter type for the contract function `tokensOf`.
+#[derive(Serialize, SchemaType)]
+struct TokensOfParams {
+    /// The address to list the balances of.
+    address: Address,
+    /// The token ID to continue after, `None` to start from the first token.
+    cursor: Option<ContractTokenId>,
+    /// The maximum number of balances to return, at most `MAX_PAGE_LIMIT`.
+    limit: u32,
+}
+
+/// The response type for the contract function `tokensOf`.
+#[derive(Debug, Serialize, SchemaType)]
+struct TokensOfResponse {
+    /// The token IDs held by the address and their balance.
+    #[concordium(size_length = 2)]
+    tokens: Vec<(ContractTokenId, ContractTokenAmount)>,
+    /// The cursor for the next page, `None` if there are no more tokens.
+    cursor: Option<ContractTokenId>,
+}
+
+/// The parameter type for the contract function `operatorsOf`.
+#[derive(Serialize, SchemaType)]
+struct OperatorsOfParams {
+    /// The address to list the operators of.
+    owner: Address,
+    /// The operator to continue after, `None` to start from the first
+    /// operator.
+    cursor: Option<Address>,
+    /// The maximum number of operators to return, at most `MAX_PAGE_LIMIT`.
+    limit: u32,
+}
+
+/// The response type for the contract function `operatorsOf`.
+#[derive(Debug, Serialize, SchemaType)]
+struct OperatorsOfResponse {
+    /// The operators of the address and the time at which they expire, if
+    /// any.
+    #[concordium(size_length = 2)]
+    operators: Vec<(Address, Option<Timestamp>)>,
+    /// The cursor for the next page, `None` if there are no more operators.
+    cursor: Option<Address>,
+}
+
+/// Get a page of the token IDs in the contract together with their total
+/// supply.
+///
+/// It rejects if:
+/// - It fails to parse the parameter.
+#[receive(
+    contract = "CIS2-Multi",
+    name = "viewTokens",
+    parameter = "ViewTokensParams",
+    return_value = "ViewTokensResponse",
+    error = "ContractError"
+)]
+fn contract_view_tokens<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<ViewTokensResponse> {
+    // Parse the parameter.
+    let params: ViewTokensParams = ctx.parameter_cursor().get()?;
+    let tokens = host
+        .state()
+        .tokens
+        .iter()
+        .map(|(token_id, token_state)| (token_id.to_owned(), token_state.supply));
+    let (tokens, cursor) = paginate(tokens, params.cursor, params.limit);
+    Ok(ViewTokensResponse { tokens, cursor })
+}
+
+/// Get a page of the addresses holding a given token ID together with their
+/// balance.
+///
+/// It rejects if:
+/// - It fails to parse the parameter.
+/// - The `token_id` does not exist.
+#[receive(
+    contract = "CIS2-Multi",
+    name = "holdersOf",
+    parameter = "HoldersOfParams",
+    return_value = "HoldersOfResponse",
+    error = "ContractError"
+)]
+fn contract_holders_of<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<HoldersOfResponse> {
+    // Parse the parameter.
+    let params: HoldersOfParams = ctx.parameter_cursor().get()?;
+    let state = host.state();
+    ensure!(
+        state.contains_token(&params.token_id),
+        ContractError::InvalidTokenId
+    );
+    let (holders, cursor) = match state.holders.get(&params.token_id) {
+        Some(token_holders) => {
+            let holders = token_holders.iter().map(|address| {
+                let balance = state
+                    .balance(&params.token_id, &address)
+                    .unwrap_or_else(|_| 0.into());
+                (*address, balance)
+            });
+            paginate(holders, params.cursor, params.limit)
+        }
+        None => (Vec::new(), None),
+    };
+    Ok(HoldersOfResponse { holders, cursor })
+}
+
+/// Get a page of the token IDs held by a given address together with their
+/// balance.
+///
+/// It rejects if:
+/// - It fails to parse the parameter.
+#[receive(
+    contract = "CIS2-Multi",
+    name = "tokensOf",
+    parameter = "TokensOfParams",
+    return_value = "TokensOfResponse",
+    error = "ContractError"
+)]
+fn contract_tokens_of<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<TokensOfResponse> {
+    // Parse the parameter.
+    let params: TokensOfParams = ctx.parameter_cursor().get()?;
+    let (tokens, cursor) = match host.state().state.get(&params.address) {
+        Some(address_state) => {
+            let balances = address_state
+                .balances
+                .iter()
+                .map(|(token_id, balance)| (token_id.to_owned(), *balance))
+                .filter(|(_, balance)| *balance > 0.into());
+            paginate(balances, params.cursor, params.limit)
+        }
+        None => (Vec::new(), None),
+    };
+    Ok(TokensOfResponse { tokens, cursor })
+}
+
+/// Get a page of the operators of a given address together with the time at
+/// which they expire. Expired operators are not listed.
+///
+/// It rejects if:
+/// - It fails to parse the parameter.
+#[receive(
+    contract = "CIS2-Multi",
+    name = "operatorsOf",
+    parameter = "OperatorsOfParams",
+    return_value = "OperatorsOfResponse",
+    error = "ContractError"
+)]
+fn contract_operators_of<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<OperatorsOfResponse> {
+    // Parse the parameter.
+    let params: OperatorsOfParams = ctx.parameter_cursor().get()?;
+    let now = ctx.metadata().slot_time();
+    let (operators, cursor) = match host.state().state.get(&params.owner) {
+        Some(address_state) => {
+            let operators = address_state
+                .operators
+                .iter()
+                .map(|(operator, expiry)| (*operator, *expiry))
+                .filter(|(_, expiry)| match expiry {
+                    Some(expiry) => now < *expiry,
+                    None => true,
+                });
+            paginate(operators, params.cursor, params.limit)
+        }
+        None => (Vec::new(), None),
+    };
+    Ok(OperatorsOfResponse { operators, cursor })
+}
+
 /// Mint new tokens with a given address as the owner of these tokens.
-/// Can only be called by the contract owner.
+/// Can only be called by addresses with the `Minter` role.
 /// Logs a `Mint` and a `TokenMetadata` event for each token.
-/// The url for the token metadata is the token ID encoded in hex, appended on
-/// the `TOKEN_METADATA_BASE_URL`.
+/// The metadata URL of a token type without metadata is derived from the base
+/// URL, by replacing `{id}` with the token ID encoded in hex or appending it.
 ///
 /// It rejects if:
-/// - The sender is not the contract instance owner.
+/// - The sender does not have the `Minter` role.
+/// - The contract is paused.
 /// - Fails to parse parameter.
 /// - Any of the tokens fails to be minted, which could be if:
+///     - The token ID is already minted.
+///     - The hash of the token metadata is invalid.
+///     - The royalties sum to more than the whole sale price.
+///     - No metadata is given and no base URL is set.
+///     - The amount is above the maximum supply of the token type.
 ///     - Fails to log Mint event.
 ///     - Fails to log TokenMetadata event.
 ///
@@ -432,12 +2512,17 @@ fn contract_mint<S: HasStateApi>(
     host: &mut impl HasHost<State<S>, StateApiType = S>,
     logger: &mut impl HasLogger,
 ) -> ContractResult<()> {
-    // Get the contract owner
-    let owner = ctx.owner();
     // Get the sender of the transaction
     let sender = ctx.sender();
 
-    ensure!(sender.matches_account(&owner), ContractError::Unauthorized);
+    ensure!(
+        host.state().has_role(&sender, Role::Minter),
+        ContractError::Unauthorized
+    );
+    ensure!(
+        !host.state().paused,
+        ContractError::Custom(CustomContractError::Paused)
+    );
 
     // Parse the parameter.
     let params: MintParams = ctx.parameter_cursor().get()?;
@@ -448,20 +2533,28 @@ fn contract_mint<S: HasStateApi>(
             state.contains_token(&token_id).eq(&false),
             ContractError::Custom(CustomContractError::TokenAlreadyMinted)
         );
+        if let Some(metadata) = &token_info.metadata {
+            metadata.ensure_valid_hash()?;
+        }
+        if let Some(royalties) = &token_info.royalties {
+            ensure_valid_royalties(royalties)?;
+        }
 
         // Mint the token in the state.
-        state.mint(
+        state.create_token(
             &token_id,
-            &token_info.0,
-            token_info.1,
-            &params.owner,
-            builder,
+            token_info.metadata.as_ref(),
+            token_info.max_supply,
+            token_info.soulbound,
+            token_info.royalties,
         );
+        let metadata_url = state.metadata_url(&token_id)?;
+        state.mint(&token_id, token_info.amount, &params.owner, builder)?;
 
         // Event for minted token.
         logger.log(&Cis2Event::Mint(MintEvent {
-            token_id,
-            amount: token_info.1,
+            token_id: token_id.to_owned(),
+            amount: token_info.amount,
             owner: params.owner,
         }))?;
 
@@ -469,646 +2562,5014 @@ fn contract_mint<S: HasStateApi>(
         logger.log(&Cis2Event::TokenMetadata::<_, ContractTokenAmount>(
             TokenMetadataEvent {
                 token_id,
-                metadata_url: token_info.0.to_metadata_url(),
+                metadata_url,
             },
         ))?;
     }
     Ok(())
 }
 
-type TransferParameter = TransferParams<ContractTokenId, ContractTokenAmount>;
-
-/// Execute a list of token transfers, in the order of the list.
-///
-/// Logs a `Transfer` event and invokes a receive hook function for every
-/// transfer in the list.
+/// Mint additional tokens of existing token types with a given address as the
+/// owner of these tokens.
+/// Can only be called by addresses with the `Minter` role.
+/// Logs a `Mint` event for each token.
 ///
 /// It rejects if:
-/// - It fails to parse the parameter.
-/// - Any of the transfers fail to be executed, which could be if:
-///     - The `token_id` does not exist.
-///     - The sender is not the owner of the token, or an operator for this
-///       specific `token_id` and `from` address.
-///     - The token is not owned by the `from`.
-/// - Fails to log event.
-/// - Any of the receive hook function calls rejects.
+/// - The sender does not have the `Minter` role.
+/// - The contract is paused.
+/// - Fails to parse parameter.
+/// - Any of the tokens fails to be minted, which could be if:
+///     - The token ID does not exist.
+///     - The supply would exceed the maximum supply of the token type.
+///     - Fails to log Mint event.
+///
+/// Note: Can at most mint 64 token types in one call due to the limit on the
+/// number of logs a smart contract can produce on each function call.
 #[receive(
     contract = "CIS2-Multi",
-    name = "transfer",
-    parameter = "TransferParameter",
+    name = "mintMore",
+    parameter = "MintMoreParams",
     error = "ContractError",
     enable_logger,
     mutable
 )]
-fn contract_transfer<S: HasStateApi>(
+fn contract_mint_more<S: HasStateApi>(
     ctx: &impl HasReceiveContext,
     host: &mut impl HasHost<State<S>, StateApiType = S>,
     logger: &mut impl HasLogger,
 ) -> ContractResult<()> {
-    // Parse the parameter.
-    let TransferParams(transfers): TransferParameter = ctx.parameter_cursor().get()?;
-    // Get the sender who invoked this contract function.
+    // Get the sender of the transaction
     let sender = ctx.sender();
 
-    for Transfer {
-        token_id,
-        amount,
-        from,
-        to,
-        data,
-    } in transfers
-    {
-        let (state, builder) = host.state_and_builder();
-        // Authenticate the sender for this transfer
-        ensure!(
-            from == sender || state.is_operator(&sender, &from),
-            ContractError::Unauthorized
-        );
-        let to_address = to.address();
-        // Update the contract state
-        state.transfer(&token_id, amount, &from, &to_address, builder)?;
+    ensure!(
+        host.state().has_role(&sender, Role::Minter),
+        ContractError::Unauthorized
+    );
+    ensure!(
+        !host.state().paused,
+        ContractError::Custom(CustomContractError::Paused)
+    );
+
+    // Parse the parameter.
+    let params: MintMoreParams = ctx.parameter_cursor().get()?;
+
+    let (state, builder) = host.state_and_builder();
+    for (token_id, amount) in params.tokens {
+        // Mint the token in the state.
+        state.mint(&token_id, amount, &params.owner, builder)?;
 
-        // Log transfer event
-        logger.log(&Cis2Event::Transfer(TransferEvent {
+        // Event for minted token.
+        logger.log(&Cis2Event::Mint(MintEvent {
             token_id,
             amount,
-            from,
-            to: to_address,
+            owner: params.owner,
         }))?;
-
-        // If the receiver is a contract we invoke it.
-        if let Receiver::Contract(address, entrypoint_name) = to {
-            let parameter = OnReceivingCis2Params {
-                token_id,
-                amount,
-                from,
-                data,
-            };
-            host.invoke_contract(
-                &address,
-                &parameter,
-                entrypoint_name.as_entrypoint_name(),
-                Amount::zero(),
-            )?;
-        }
     }
     Ok(())
 }
 
-/// Enable or disable addresses as operators of the sender address.
-/// Logs an `UpdateOperator` event.
+/// Configure the drop of an existing token type, allowing any account to mint
+/// tokens of this type with `publicMint` while the drop is open.
+/// Reconfiguring a drop keeps the amounts already minted in the drop.
+/// Can only be called by addresses with the `Admin` role.
 ///
 /// It rejects if:
-/// - It fails to parse the parameter.
-/// - Fails to log event.
+/// - The sender does not have the `Admin` role.
+/// - Fails to parse parameter.
+/// - The token ID does not exist.
 #[receive(
     contract = "CIS2-Multi",
-    name = "updateOperator",
-    parameter = "UpdateOperatorParams",
+    name = "setDrop",
+    parameter = "SetDropParams",
     error = "ContractError",
-    enable_logger,
     mutable
 )]
-fn contract_update_operator<S: HasStateApi>(
+fn contract_set_drop<S: HasStateApi>(
     ctx: &impl HasReceiveContext,
     host: &mut impl HasHost<State<S>, StateApiType = S>,
-    logger: &mut impl HasLogger,
 ) -> ContractResult<()> {
+    // Authorize the sender.
+    ensure!(
+        host.state().has_role(&ctx.sender(), Role::Admin),
+        ContractError::Unauthorized
+    );
     // Parse the parameter.
-    let UpdateOperatorParams(params) = ctx.parameter_cursor().get()?;
-    // Get the sender who invoked this contract function.
-    let sender = ctx.sender();
+    let params: SetDropParams = ctx.parameter_cursor().get()?;
 
     let (state, builder) = host.state_and_builder();
-    for param in params {
-        // Update the operator in the state.
-        match param.update {
-            OperatorUpdate::Add => state.add_operator(&sender, &param.operator, builder),
-            OperatorUpdate::Remove => state.remove_operator(&sender, &param.operator),
-        }
-
-        // Log the appropriate event
-        logger.log(
-            &Cis2Event::<ContractTokenId, ContractTokenAmount>::UpdateOperator(
-                UpdateOperatorEvent {
-                    owner: sender,
-                    operator: param.operator,
-                    update: param.update,
-                },
-            ),
-        )?;
-    }
+    ensure!(
+        state.contains_token(&params.token_id),
+        ContractError::InvalidTokenId
+    );
+    state.set_drop(&params.token_id, params.config, builder);
     Ok(())
 }
 
-/// Parameter type for the CIS-2 function `balanceOf` specialized to the subset
-/// of TokenIDs used by this contract.
-type ContractBalanceOfQueryParams = BalanceOfQueryParams<ContractTokenId>;
-
-/// Response type for the CIS-2 function `balanceOf` specialized to the subset
-/// of TokenAmounts used by this contract.
-type ContractBalanceOfQueryResponse = BalanceOfQueryResponse<ContractTokenAmount>;
-
-/// Get the balance of given token IDs and addresses.
+/// Set the account receiving the payments of the drops.
+/// Can only be called by addresses with the `Admin` role.
 ///
 /// It rejects if:
-/// - It fails to parse the parameter.
-/// - Any of the queried `token_id` does not exist.
+/// - The sender does not have the `Admin` role.
+/// - Fails to parse parameter.
 #[receive(
     contract = "CIS2-Multi",
-    name = "balanceOf",
-    parameter = "ContractBalanceOfQueryParams",
-    return_value = "ContractBalanceOfQueryResponse",
-    error = "ContractError"
+    name = "setTreasury",
+    parameter = "AccountAddress",
+    error = "ContractError",
+    mutable
 )]
-fn contract_balance_of<S: HasStateApi>(
+fn contract_set_treasury<S: HasStateApi>(
     ctx: &impl HasReceiveContext,
-    host: &impl HasHost<State<S>, StateApiType = S>,
-) -> ContractResult<ContractBalanceOfQueryResponse> {
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<()> {
+    // Authorize the sender.
+    ensure!(
+        host.state().has_role(&ctx.sender(), Role::Admin),
+        ContractError::Unauthorized
+    );
     // Parse the parameter.
-    let params: ContractBalanceOfQueryParams = ctx.parameter_cursor().get()?;
-    // Build the response.
-    let mut response = Vec::with_capacity(params.queries.len());
-    for query in params.queries {
-        // Query the state for balance.
-        let amount = host.state().balance(&query.token_id, &query.address)?;
-        response.push(amount);
-    }
-    let result = ContractBalanceOfQueryResponse::from(response);
-    Ok(result)
+    let treasury: AccountAddress = ctx.parameter_cursor().get()?;
+    host.state_mut().treasury = treasury;
+    Ok(())
 }
 
-/// Takes a list of queries. Each query is an owner address and some address to
-/// check as an operator of the owner address.
+/// Mint tokens in the drop of a token type to the sender, paying the price of
+/// the tokens. The payment is forwarded to the treasury account.
+/// Logs a `Mint` event.
 ///
 /// It rejects if:
-/// - It fails to parse the parameter.
+/// - The contract is paused.
+/// - The sender is not an account.
+/// - Fails to parse parameter.
+/// - No drop is configured for the token type.
+/// - The drop is not open at the current time.
+/// - The amount exceeds the amount of tokens remaining in the drop.
+/// - The amount exceeds the amount of tokens remaining for the sender.
+/// - The CCD amount sent does not match the price of the tokens.
+/// - The amount is above the maximum supply of the token type.
+/// - Fails to log Mint event.
+/// - Fails to transfer the payment to the treasury.
 #[receive(
     contract = "CIS2-Multi",
-    name = "operatorOf",
-    parameter = "OperatorOfQueryParams",
-    return_value = "OperatorOfQueryResponse",
-    error = "ContractError"
+    name = "publicMint",
+    parameter = "PublicMintParams",
+    error = "ContractError",
+    enable_logger,
+    mutable,
+    payable
 )]
-fn contract_operator_of<S: HasStateApi>(
+fn contract_public_mint<S: HasStateApi>(
     ctx: &impl HasReceiveContext,
-    host: &impl HasHost<State<S>, StateApiType = S>,
-) -> ContractResult<OperatorOfQueryResponse> {
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    amount: Amount,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    ensure!(
+        !host.state().paused,
+        ContractError::Custom(CustomContractError::Paused)
+    );
+    // Only accounts can buy tokens, as the wallet limit is tracked per account.
+    let sender = if let Address::Account(account) = ctx.sender() {
+        account
+    } else {
+        bail!(CustomContractError::AccountOnly.into())
+    };
     // Parse the parameter.
-    let params: OperatorOfQueryParams = ctx.parameter_cursor().get()?;
-    // Build the response.
-    let mut response = Vec::with_capacity(params.queries.len());
-    for query in params.queries {
-        // Query the state for address being an operator of owner.
-        let is_operator = host.state().is_operator(&query.address, &query.owner);
-        response.push(is_operator);
-    }
-    let result = OperatorOfQueryResponse::from(response);
-    Ok(result)
-}
+    let params: PublicMintParams = ctx.parameter_cursor().get()?;
+    let owner = Address::Account(sender);
 
-/// Parameter type for the CIS-2 function `tokenMetadata` specialized to the
-/// subset of TokenIDs used by this contract.
-type ContractTokenMetadataQueryParams = TokenMetadataQueryParams<ContractTokenId>;
+    let (state, builder) = host.state_and_builder();
+    let price = state.record_drop_mint(
+        &params.token_id,
+        params.amount,
+        &sender,
+        ctx.metadata().slot_time(),
+    )?;
+    ensure!(
+        amount == price,
+        ContractError::Custom(CustomContractError::IncorrectPayment)
+    );
+    state.mint(&params.token_id, params.amount, &owner, builder)?;
 
-/// Get the token metadata URLs and checksums given a list of token IDs.
+    // Event for minted token.
+    logger.log(&Cis2Event::Mint(MintEvent {
+        token_id: params.token_id,
+        amount: params.amount,
+        owner,
+    }))?;
+
+    // Forward the payment to the treasury.
+    let treasury = host.state().treasury;
+    host.invoke_transfer(&treasury, amount)
+        .map_err(|_| CustomContractError::InvokeTransferError)?;
+    Ok(())
+}
+
+/// Set or remove the allowlist of the drop of a token type, as the root of a
+/// Merkle tree over the allowlist entries. See the `merkle` module for how
+/// the tree is built.
+/// Can only be called by addresses with the `Admin` role.
 ///
 /// It rejects if:
-/// - It fails to parse the parameter.
-/// - Any of the queried `token_id` does not exist.
+/// - The sender does not have the `Admin` role.
+/// - Fails to parse parameter.
+/// - No drop is configured for the token type.
 #[receive(
     contract = "CIS2-Multi",
-    name = "tokenMetadata",
-    parameter = "ContractTokenMetadataQueryParams",
-    return_value = "TokenMetadataQueryResponse",
-    error = "ContractError"
+    name = "setAllowlist",
+    parameter = "SetAllowlistParams",
+    error = "ContractError",
+    mutable
 )]
-fn contract_token_metadata<S: HasStateApi>(
+fn contract_set_allowlist<S: HasStateApi>(
     ctx: &impl HasReceiveContext,
-    host: &impl HasHost<State<S>, StateApiType = S>,
-) -> ContractResult<TokenMetadataQueryResponse> {
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<()> {
+    // Authorize the sender.
+    ensure!(
+        host.state().has_role(&ctx.sender(), Role::Admin),
+        ContractError::Unauthorized
+    );
     // Parse the parameter.
-    let params: ContractTokenMetadataQueryParams = ctx.parameter_cursor().get()?;
-    // Build the response.
-    let mut response = Vec::with_capacity(params.queries.len());
-    for token_id in params.queries {
-        let metadata_url: MetadataUrl = match host
-            .state()
-            .tokens
-            .get(&token_id)
-            .map(|metadata| metadata.to_owned())
-        {
-            Option::Some(m) => Result::Ok(m),
-            Option::None => Result::Err(ContractError::InvalidTokenId),
-        }?;
+    let params: SetAllowlistParams = ctx.parameter_cursor().get()?;
 
-        response.push(metadata_url);
-    }
-    let result = TokenMetadataQueryResponse::from(response);
-    Ok(result)
+    let mut drop = host
+        .state_mut()
+        .drops
+        .entry(params.token_id)
+        .occupied_or(ContractError::Custom(CustomContractError::DropNotFound))?;
+    drop.allowlist_root = params.root;
+    Ok(())
 }
 
-/// Example of implementing a function for receiving transfers.
-/// It is not required to be implemented by the token contract, but is required
-/// to implement such a function by any contract which should receive CIS2
-/// tokens.
-///
-/// This contract function is called when a token is transferred to an instance
-/// of this contract and should only be called by a contract implementing CIS2.
-/// The parameter include a `data` field which can be used to
-/// implement some arbitrary functionality. In this example we choose not to use
-/// it, and define the function to forward any transfers to the owner of the
-/// contract instance.
-///
-/// Note: The name of this function is not part the CIS2, and a contract can
-/// have multiple functions for receiving tokens.
+/// Mint tokens in the drop of a token type to the sender through the
+/// allowlist of the drop, paying the price of the tokens. The sender proves it
+/// is allowlisted with a Merkle proof of its entry, and can mint at most its
+/// allowance in total. The tokens count towards the limit of the drop, but
+/// not towards the wallet limit of `publicMint`. The payment is forwarded to
+/// the treasury account.
+/// Logs a `Mint` event.
 ///
 /// It rejects if:
-/// - Sender is not a contract.
-/// - It fails to parse the parameter.
-/// - Contract name part of the parameter is invalid.
-/// - Calling back `transfer` to sender contract rejects.
+/// - The contract is paused.
+/// - The sender is not an account.
+/// - Fails to parse parameter.
+/// - No drop is configured for the token type.
+/// - The drop has no allowlist.
+/// - The proof of the allowlist entry is invalid.
+/// - The amount exceeds the allowance remaining for the sender.
+/// - The drop is not open at the current time.
+/// - The amount exceeds the amount of tokens remaining in the drop.
+/// - The CCD amount sent does not match the price of the tokens.
+/// - The amount is above the maximum supply of the token type.
+/// - Fails to log Mint event.
+/// - Fails to transfer the payment to the treasury.
 #[receive(
     contract = "CIS2-Multi",
-    name = "onReceivingCIS2",
-    error = "ContractError"
+    name = "allowlistMint",
+    parameter = "AllowlistMintParams",
+    error = "ContractError",
+    crypto_primitives,
+    enable_logger,
+    mutable,
+    payable
 )]
-fn contract_on_cis2_received<S: HasStateApi>(
+fn contract_allowlist_mint<S: HasStateApi>(
     ctx: &impl HasReceiveContext,
-    host: &impl HasHost<State<S>, StateApiType = S>,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    amount: Amount,
+    logger: &mut impl HasLogger,
+    crypto_primitives: &impl HasCryptoPrimitives,
 ) -> ContractResult<()> {
-    // Ensure the sender is a contract.
-    let sender = if let Address::Contract(contract) = ctx.sender() {
-        contract
+    ensure!(
+        !host.state().paused,
+        ContractError::Custom(CustomContractError::Paused)
+    );
+    // Only accounts can be allowlisted.
+    let sender = if let Address::Account(account) = ctx.sender() {
+        account
     } else {
-        bail!(CustomContractError::ContractOnly.into())
+        bail!(CustomContractError::AccountOnly.into())
     };
-
     // Parse the parameter.
-    let params: OnReceivingCis2Params<ContractTokenId, ContractTokenAmount> =
-        ctx.parameter_cursor().get()?;
+    let params: AllowlistMintParams = ctx.parameter_cursor().get()?;
+    let owner = Address::Account(sender);
 
-    // Build the transfer from this contract to the contract owner.
-    let transfer = Transfer {
+    let (state, builder) = host.state_and_builder();
+    let price = state.record_allowlist_mint(
+        &params.token_id,
+        params.amount,
+        &sender,
+        params.allowance,
+        &params.proof,
+        ctx.metadata().slot_time(),
+        crypto_primitives,
+    )?;
+    ensure!(
+        amount == price,
+        ContractError::Custom(CustomContractError::IncorrectPayment)
+    );
+    state.mint(&params.token_id, params.amount, &owner, builder)?;
+
+    // Event for minted token.
+    logger.log(&Cis2Event::Mint(MintEvent {
         token_id: params.token_id,
         amount: params.amount,
-        from: Address::Contract(ctx.self_address()),
-        to: Receiver::from_account(ctx.owner()),
-        data: AdditionalData::empty(),
-    };
+        owner,
+    }))?;
 
-    let parameter = TransferParams::from(vec![transfer]);
-
-    // Send back a transfer
-    host.invoke_contract_read_only(
-        &sender,
-        &parameter,
-        EntrypointName::new("transfer")?,
-        Amount::zero(),
-    )?;
+    // Forward the payment to the treasury.
+    let treasury = host.state().treasury;
+    host.invoke_transfer(&treasury, amount)
+        .map_err(|_| CustomContractError::InvokeTransferError)?;
     Ok(())
 }
 
-/// Get the supported standards or addresses for a implementation given list of
-/// standard identifiers.
+/// The response type for the contract function `viewDrop`.
+#[derive(Debug, Serialize, SchemaType)]
+struct ViewDropResponse {
+    /// The configuration of the drop.
+    config: DropConfig,
+    /// The amount of tokens minted in the drop.
+    minted: ContractTokenAmount,
+    /// The root of the Merkle tree over the allowlist of the drop, if any.
+    allowlist_root: Option<MerkleHash>,
+}
+
+/// Get the configuration of the drop of a token type and the amount of tokens
+/// minted in it.
 ///
 /// It rejects if:
-/// - It fails to parse the parameter.
+/// - Fails to parse parameter.
+/// - No drop is configured for the token type.
 #[receive(
     contract = "CIS2-Multi",
-    name = "supports",
-    parameter = "SupportsQueryParams",
-    return_value = "SupportsQueryResponse",
+    name = "viewDrop",
+    parameter = "ContractTokenId",
+    return_value = "ViewDropResponse",
     error = "ContractError"
 )]
-fn contract_supports<S: HasStateApi>(
+fn contract_view_drop<S: HasStateApi>(
     ctx: &impl HasReceiveContext,
     host: &impl HasHost<State<S>, StateApiType = S>,
-) -> ContractResult<SupportsQueryResponse> {
+) -> ContractResult<ViewDropResponse> {
     // Parse the parameter.
-    let params: SupportsQueryParams = ctx.parameter_cursor().get()?;
+    let token_id: ContractTokenId = ctx.parameter_cursor().get()?;
+    let drop = host
+        .state()
+        .drops
+        .get(&token_id)
+        .ok_or(ContractError::Custom(CustomContractError::DropNotFound))?;
+    Ok(ViewDropResponse {
+        config: drop.config.clone(),
+        minted: drop.minted,
+        allowlist_root: drop.allowlist_root,
+    })
+}
 
-    // Build the response.
-    let mut response = Vec::with_capacity(params.queries.len());
-    for std_id in params.queries {
-        if SUPPORTS_STANDARDS.contains(&std_id.as_standard_identifier()) {
-            response.push(SupportResult::Support);
-        } else {
-            response.push(host.state().have_implementors(&std_id));
-        }
-    }
-    let result = SupportsQueryResponse::from(response);
-    Ok(result)
+/// Check whether the tokens of a token type are soulbound, i.e.
+/// non-transferable. The support of this query is reported by `supports` with
+/// the `CIS2-Soulbound` standard identifier.
+///
+/// It rejects if:
+/// - Fails to parse parameter.
+/// - The token ID does not exist.
+#[receive(
+    contract = "CIS2-Multi",
+    name = "isSoulbound",
+    parameter = "ContractTokenId",
+    return_value = "bool",
+    error = "ContractError"
+)]
+fn contract_is_soulbound<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<bool> {
+    // Parse the parameter.
+    let token_id: ContractTokenId = ctx.parameter_cursor().get()?;
+    host.state().is_soulbound(&token_id)
 }
 
-/// Set the addresses for an implementation given a standard identifier and a
-/// list of contract addresses.
+/// Update the metadata URLs of existing token types.
+/// Can only be called by addresses with the `MetadataUpdater` role.
+/// Logs a `TokenMetadata` event for each token.
 ///
 /// It rejects if:
-/// - Sender is not the owner of the contract instance.
-/// - It fails to parse the parameter.
+/// - The sender does not have the `MetadataUpdater` role.
+/// - Fails to parse parameter.
+/// - Any of the token types fails to be updated, which could be if:
+///     - The token ID does not exist.
+///     - The hash of the token metadata is invalid.
+///     - The metadata of the token type, or of every token type, is frozen.
+///     - Fails to log TokenMetadata event.
 #[receive(
     contract = "CIS2-Multi",
-    name = "setImplementors",
-    parameter = "SetImplementorsParams",
+    name = "updateTokenMetadata",
+    parameter = "UpdateTokenMetadataParams",
     error = "ContractError",
+    enable_logger,
     mutable
 )]
-fn contract_set_implementor<S: HasStateApi>(
+fn contract_update_token_metadata<S: HasStateApi>(
     ctx: &impl HasReceiveContext,
     host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
 ) -> ContractResult<()> {
     // Authorize the sender.
     ensure!(
-        ctx.sender().matches_account(&ctx.owner()),
+        host.state().has_role(&ctx.sender(), Role::MetadataUpdater),
         ContractError::Unauthorized
     );
     // Parse the parameter.
-    let params: SetImplementorsParams = ctx.parameter_cursor().get()?;
-    // Update the implementors in the state
-    host.state_mut()
-        .set_implementors(params.id, params.implementors);
-    Ok(())
-}
-
-// Tests
-
-#[concordium_cfg_test]
-mod tests {
-    use super::*;
-    use test_infrastructure::*;
+    let UpdateTokenMetadataParams(params) = ctx.parameter_cursor().get()?;
 
-    const ACCOUNT_0: AccountAddress = AccountAddress([0u8; 32]);
-    const ADDRESS_0: Address = Address::Account(ACCOUNT_0);
-    const ACCOUNT_1: AccountAddress = AccountAddress([1u8; 32]);
-    const ADDRESS_1: Address = Address::Account(ACCOUNT_1);
-    const TOKEN_0: ContractTokenId = TokenIdU8(2);
-    const TOKEN_1: ContractTokenId = TokenIdU8(42);
+    let state = host.state_mut();
+    for UpdateTokenMetadataParam { token_id, metadata } in params {
+        // Update the metadata in the state.
+        metadata.ensure_valid_hash()?;
+        state.update_metadata(&token_id, &metadata)?;
 
-    /// Test helper function which creates a contract state with two tokens with
-    /// id `TOKEN_0` and id `TOKEN_1` owned by `ADDRESS_0`
-    fn initial_state<S: HasStateApi>(state_builder: &mut StateBuilder<S>) -> State<S> {
-        let mut state = State::empty(state_builder);
-        state.mint(
-            &TOKEN_0,
-            &{
-                let url = "url".to_owned();
-                let hash =
-                    "db2ca420a0090593ac6559ff2a98ce30abfe665d7a18ff3c63883e8b98622a73".to_owned();
-                TokenMetadata { url, hash }
-            },
-            400.into(),
-            &ADDRESS_0,
-            state_builder,
-        );
-        state.mint(
-            &TOKEN_1,
-            &{
-                let url = "url".to_owned();
-                let hash =
-                    "db2ca420a0090593ac6559ff2a98ce30abfe665d7a18ff3c63883e8b98622a73".to_owned();
-                TokenMetadata { url, hash }
+        // Metadata URL for the token.
+        logger.log(&Cis2Event::TokenMetadata::<_, ContractTokenAmount>(
+            TokenMetadataEvent {
+                token_id,
+                metadata_url: metadata.to_metadata_url(),
             },
-            1.into(),
-            &ADDRESS_0,
-            state_builder,
-        );
-        state
+        ))?;
     }
+    Ok(())
+}
 
-    /// Test initialization succeeds with a state with no tokens.
-    #[concordium_test]
-    fn test_init() {
-        // Setup the context
-        let ctx = TestInitContext::empty();
-        let mut builder = TestStateBuilder::new();
-
-        // Call the contract function.
-        let result = contract_init(&ctx, &mut builder);
-
-        // Check the result
-        let state = result.expect_report("Contract initialization failed");
-
-        // Check the state
-        claim_eq!(
-            state.tokens.iter().count(),
-            0,
-            "Only one token is initialized"
-        );
-    }
+/// Set the base URL the metadata URLs of the token types are derived from.
+/// Token types with an explicit metadata URL are not affected.
+/// Can only be called by addresses with the `Admin` role.
+///
+/// Note: No `TokenMetadata` events are logged for the affected token types, as
+/// their number is unbounded.
+///
+/// It rejects if:
+/// - The sender does not have the `Admin` role.
+/// - Fails to parse parameter.
+/// - The metadata of every token type is frozen.
+/// - A reveal has been committed to, whether or not it has happened.
+#[receive(
+    contract = "CIS2-Multi",
+    name = "setBaseUrl",
+    parameter = "String",
+    error = "ContractError",
+    mutable
+)]
+fn contract_set_base_url<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<()> {
+    // Authorize the sender.
+    ensure!(
+        host.state().has_role(&ctx.sender(), Role::Admin),
+        ContractError::Unauthorized
+    );
+    // Parse the parameter.
+    let base_url: String = ctx.parameter_cursor().get()?;
+    host.state_mut().set_base_url(base_url)
+}
 
-    /// Test minting succeeds and the tokens are owned by the given address and
-    /// the appropriate events are logged.
-    #[concordium_test]
-    fn test_mint() {
-        // Setup the context
-        let mut ctx = TestReceiveContext::empty();
-        ctx.set_sender(ADDRESS_0);
-        ctx.set_owner(ACCOUNT_0);
+/// Hide the metadata URLs of the token types without an explicit metadata URL
+/// behind a placeholder, committing to the reveal of the final base URL.
+/// Can only be called by addresses with the `Admin` role, and only once.
+///
+/// It rejects if:
+/// - The sender does not have the `Admin` role.
+/// - Fails to parse parameter.
+/// - The metadata of the collection is frozen, as the placeholder could never
+///   be revealed.
+/// - A reveal has already been committed to.
+#[receive(
+    contract = "CIS2-Multi",
+    name = "commitReveal",
+    parameter = "CommitRevealParams",
+    error = "ContractError",
+    mutable
+)]
+fn contract_commit_reveal<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<()> {
+    // Authorize the sender.
+    ensure!(
+        host.state().has_role(&ctx.sender(), Role::Admin),
+        ContractError::Unauthorized
+    );
+    // Parse the parameter.
+    let params: CommitRevealParams = ctx.parameter_cursor().get()?;
+    host.state_mut()
+        .commit_reveal(params.placeholder, params.commitment)
+}
 
-        // and parameter.
-        let mut tokens = collections::BTreeMap::new();
-        tokens.insert(
-            TOKEN_0,
-            (
-                {
+/// Reveal the metadata URLs hidden behind the placeholder by setting the final
+/// base URL. The SHA256 hash of the serialized parameter must match the
+/// commitment of `commitReveal`.
+/// Can only be called by addresses with the `Admin` role, and only once.
+/// Logs a `Revealed` event.
+///
+/// Note: No `TokenMetadata` events are logged for the affected token types, as
+/// their number is unbounded.
+///
+/// It rejects if:
+/// - The sender does not have the `Admin` role.
+/// - Fails to parse parameter.
+/// - No reveal has been committed to.
+/// - The reveal has already happened.
+/// - The parameter does not match the commitment.
+/// - The metadata of every token type is frozen.
+/// - Fails to log event.
+#[receive(
+    contract = "CIS2-Multi",
+    name = "reveal",
+    parameter = "RevealParams",
+    error = "ContractError",
+    crypto_primitives,
+    enable_logger,
+    mutable
+)]
+fn contract_reveal<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+    crypto_primitives: &impl HasCryptoPrimitives,
+) -> ContractResult<()> {
+    // Authorize the sender.
+    ensure!(
+        host.state().has_role(&ctx.sender(), Role::Admin),
+        ContractError::Unauthorized
+    );
+    // Parse the parameter.
+    let params: RevealParams = ctx.parameter_cursor().get()?;
+    let reveal_hash = crypto_primitives.hash_sha2_256(&to_bytes(&params)).0;
+    host.state_mut()
+        .reveal(params.base_url.clone(), reveal_hash)?;
+    logger.log(&CustomEvent::Revealed(params))?;
+    Ok(())
+}
+
+/// The response type for the contract function `viewReveal`.
+#[derive(Debug, Serialize, SchemaType)]
+struct ViewRevealResponse {
+    /// The placeholder metadata URL until the reveal.
+    placeholder: Option<MetadataUrl>,
+    /// The commitment to the reveal, if any.
+    commitment: Option<[u8; 32]>,
+    /// Whether the reveal has happened.
+    revealed: bool,
+}
+
+/// Get the placeholder metadata URL, the commitment to the reveal and whether
+/// the reveal has happened.
+#[receive(
+    contract = "CIS2-Multi",
+    name = "viewReveal",
+    return_value = "ViewRevealResponse",
+    error = "ContractError"
+)]
+fn contract_view_reveal<S: HasStateApi>(
+    _ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<ViewRevealResponse> {
+    let state = host.state();
+    Ok(ViewRevealResponse {
+        placeholder: state.placeholder.clone(),
+        commitment: state.reveal_commitment,
+        revealed: state.revealed,
+    })
+}
+
+/// Permanently lock the metadata URLs of the given token types, or of every
+/// token type in the contract.
+/// Can only be called by addresses with the `MetadataUpdater` role.
+/// Logs a `MetadataFrozen` event for each token, or a single `MetadataFrozen`
+/// event without a token ID when freezing every token type.
+///
+/// It rejects if:
+/// - The sender does not have the `MetadataUpdater` role.
+/// - Fails to parse parameter.
+/// - Any of the token IDs does not exist.
+/// - The metadata is hidden until the reveal.
+/// - Fails to log event.
+#[receive(
+    contract = "CIS2-Multi",
+    name = "freezeMetadata",
+    parameter = "FreezeMetadataParams",
+    error = "ContractError",
+    enable_logger,
+    mutable
+)]
+fn contract_freeze_metadata<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    // Authorize the sender.
+    ensure!(
+        host.state().has_role(&ctx.sender(), Role::MetadataUpdater),
+        ContractError::Unauthorized
+    );
+    // Parse the parameter.
+    let params: FreezeMetadataParams = ctx.parameter_cursor().get()?;
+
+    let state = host.state_mut();
+    match params {
+        FreezeMetadataParams::Collection => {
+            ensure!(
+                !state.is_hidden(),
+                ContractError::Custom(CustomContractError::NotRevealed)
+            );
+            state.metadata_frozen = true;
+            logger.log(&CustomEvent::MetadataFrozen(MetadataFrozenEvent {
+                token_id: None,
+            }))?;
+        }
+        FreezeMetadataParams::Tokens(token_ids) => {
+            for token_id in token_ids {
+                state.freeze_token_metadata(&token_id)?;
+                logger.log(&CustomEvent::MetadataFrozen(MetadataFrozenEvent {
+                    token_id: Some(token_id),
+                }))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+type TransferParameter = TransferParams<ContractTokenId, ContractTokenAmount>;
+
+/// Execute a token transfer on behalf of `sender` at the time `now`.
+///
+/// Logs a `Transfer` event and invokes a receive hook function if the receiver
+/// is a contract.
+fn transfer<S: HasStateApi>(
+    transfer: Transfer<ContractTokenId, ContractTokenAmount>,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+    sender: Address,
+    now: Timestamp,
+) -> ContractResult<()> {
+    let Transfer {
+        token_id,
+        amount,
+        from,
+        to,
+        data,
+    } = transfer;
+
+    // Transfers are halted while the contract is paused.
+    ensure!(
+        !host.state().paused,
+        ContractError::Custom(CustomContractError::Paused)
+    );
+
+    let (state, builder) = host.state_and_builder();
+    // Authenticate the sender for this transfer. A sender which is neither the
+    // owner nor an operator of the owner spends its allowance.
+    if from != sender && !state.is_operator(&sender, &from, now) {
+        state.spend_allowance(&from, &sender, &token_id, amount)?;
+    }
+    let to_address = to.address();
+    // Update the contract state
+    state.transfer(&token_id, amount, &from, &to_address, builder)?;
+
+    // Log transfer event
+    logger.log(&Cis2Event::Transfer(TransferEvent {
+        token_id: token_id.to_owned(),
+        amount,
+        from,
+        to: to_address,
+    }))?;
+
+    // If the receiver is a contract we invoke it.
+    if let Receiver::Contract(address, entrypoint_name) = to {
+        let parameter = OnReceivingCis2Params {
+            token_id,
+            amount,
+            from,
+            data,
+        };
+        host.invoke_contract(
+            &address,
+            &parameter,
+            entrypoint_name.as_entrypoint_name(),
+            Amount::zero(),
+        )?;
+    }
+    Ok(())
+}
+
+/// Execute a list of token transfers, in the order of the list.
+///
+/// Logs a `Transfer` event and invokes a receive hook function for every
+/// transfer in the list.
+///
+/// It rejects if:
+/// - The contract is paused.
+/// - It fails to parse the parameter.
+/// - Any of the transfers fail to be executed, which could be if:
+///     - The `token_id` does not exist.
+///     - The token type is soulbound.
+///     - The sender is not the owner of the token, or an operator for this
+///       specific `token_id` and `from` address, and has no sufficient
+///       allowance for the `token_id` from the `from` address.
+///     - The token is not owned by the `from`.
+/// - Fails to log event.
+/// - Any of the receive hook function calls rejects.
+#[receive(
+    contract = "CIS2-Multi",
+    name = "transfer",
+    parameter = "TransferParameter",
+    error = "ContractError",
+    enable_logger,
+    mutable
+)]
+fn contract_transfer<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    // Parse the parameter.
+    let TransferParams(transfers): TransferParameter = ctx.parameter_cursor().get()?;
+    // Get the sender who invoked this contract function.
+    let sender = ctx.sender();
+    let now = ctx.metadata().slot_time();
+
+    for transfer_entry in transfers {
+        transfer(transfer_entry, host, logger, sender, now)?;
+    }
+    Ok(())
+}
+
+/// Burn an amount of a token type owned by a given address.
+/// Can only be called by the owner of the tokens or an operator of the owner.
+/// Addresses with the `Admin` role can also burn soulbound tokens of any owner,
+/// revoking them.
+/// Logs a `Burn` event.
+/// When the total supply of the token type reaches zero, the token type is kept
+/// with its maximum supply, so `mintMore` can mint it again up to that supply,
+/// while `mint` rejects the token ID as already minted.
+///
+/// It rejects if:
+/// - The contract is paused.
+/// - It fails to parse the parameter.
+/// - The `token_id` does not exist.
+/// - The sender is not the owner of the tokens, an operator of the owner, or
+///   an admin burning soulbound tokens.
+/// - The owner have insufficient tokens to burn.
+/// - Fails to log event.
+#[receive(
+    contract = "CIS2-Multi",
+    name = "burn",
+    parameter = "BurnParams",
+    error = "ContractError",
+    enable_logger,
+    mutable
+)]
+fn contract_burn<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    // Burning is halted while the contract is paused.
+    ensure!(
+        !host.state().paused,
+        ContractError::Custom(CustomContractError::Paused)
+    );
+    // Parse the parameter.
+    let params: BurnParams = ctx.parameter_cursor().get()?;
+    // Get the sender who invoked this contract function.
+    let sender = ctx.sender();
+
+    let state = host.state_mut();
+    // Authenticate the sender for this burn. Admins can revoke soulbound
+    // tokens.
+    ensure!(
+        params.owner == sender
+            || state.is_operator(&sender, &params.owner, ctx.metadata().slot_time())
+            || (state.has_role(&sender, Role::Admin) && state.is_soulbound(&params.token_id)?),
+        ContractError::Unauthorized
+    );
+    // Update the contract state.
+    state.burn(&params.token_id, params.amount, &params.owner)?;
+
+    // Log burn event.
+    logger.log(&Cis2Event::Burn(BurnEvent {
+        token_id: params.token_id,
+        amount: params.amount,
+        owner: params.owner,
+    }))?;
+    Ok(())
+}
+
+/// Enable or disable an address as operator of `sender` in the state. An
+/// enabled operator expires at the given time, if any.
+/// Logs an `UpdateOperator` event.
+fn update_operator<S: HasStateApi>(
+    update: OperatorUpdate,
+    sender: Address,
+    operator: Address,
+    expiry: Option<Timestamp>,
+    state: &mut State<S>,
+    builder: &mut StateBuilder<S>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    // Update the operator in the state.
+    match update {
+        OperatorUpdate::Add => state.add_operator(&sender, &operator, expiry, builder),
+        OperatorUpdate::Remove => state.remove_operator(&sender, &operator),
+    }
+
+    // Log the appropriate event
+    logger.log(
+        &Cis2Event::<ContractTokenId, ContractTokenAmount>::UpdateOperator(UpdateOperatorEvent {
+            owner: sender,
+            operator,
+            update,
+        }),
+    )?;
+    Ok(())
+}
+
+/// Enable or disable addresses as operators of the sender address.
+/// The enabled operators never expire.
+/// Logs an `UpdateOperator` event.
+///
+/// It rejects if:
+/// - It fails to parse the parameter.
+/// - Fails to log event.
+#[receive(
+    contract = "CIS2-Multi",
+    name = "updateOperator",
+    parameter = "UpdateOperatorParams",
+    error = "ContractError",
+    enable_logger,
+    mutable
+)]
+fn contract_update_operator<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    // Parse the parameter.
+    let UpdateOperatorParams(params) = ctx.parameter_cursor().get()?;
+    // Get the sender who invoked this contract function.
+    let sender = ctx.sender();
+
+    let (state, builder) = host.state_and_builder();
+    for param in params {
+        update_operator(
+            param.update,
+            sender,
+            param.operator,
+            None,
+            state,
+            builder,
+            logger,
+        )?;
+    }
+    Ok(())
+}
+
+/// Enable or disable addresses as operators of the sender address, like
+/// `updateOperator`, except the enabled operators expire at the given time.
+/// Logs an `UpdateOperator` event.
+///
+/// It rejects if:
+/// - It fails to parse the parameter.
+/// - The expiry has already passed.
+/// - Fails to log event.
+#[receive(
+    contract = "CIS2-Multi",
+    name = "updateOperatorWithExpiry",
+    parameter = "UpdateOperatorWithExpiryParams",
+    error = "ContractError",
+    enable_logger,
+    mutable
+)]
+fn contract_update_operator_with_expiry<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    // Parse the parameter.
+    let UpdateOperatorWithExpiryParams {
+        updates: UpdateOperatorParams(params),
+        expiry,
+    } = ctx.parameter_cursor().get()?;
+    ensure!(
+        expiry > ctx.metadata().slot_time(),
+        ContractError::Custom(CustomContractError::InvalidExpiry)
+    );
+    // Get the sender who invoked this contract function.
+    let sender = ctx.sender();
+
+    let (state, builder) = host.state_and_builder();
+    for param in params {
+        update_operator(
+            param.update,
+            sender,
+            param.operator,
+            Some(expiry),
+            state,
+            builder,
+            logger,
+        )?;
+    }
+    Ok(())
+}
+
+/// Set the amount of tokens of a token type a spender can transfer on behalf
+/// of the sender, replacing any previous allowance.
+/// Allowances are independent of operators: an operator can transfer any
+/// amount of tokens, regardless of its allowance.
+/// Logs an `Approval` event.
+///
+/// It rejects if:
+/// - It fails to parse the parameter.
+/// - Fails to log event.
+#[receive(
+    contract = "CIS2-Multi",
+    name = "approve",
+    parameter = "ApproveParams",
+    error = "ContractError",
+    enable_logger,
+    mutable
+)]
+fn contract_approve<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    // Parse the parameter.
+    let params: ApproveParams = ctx.parameter_cursor().get()?;
+    // Get the sender who invoked this contract function.
+    let sender = ctx.sender();
+
+    let (state, builder) = host.state_and_builder();
+    state.set_allowance(
+        &sender,
+        &params.spender,
+        &params.token_id,
+        params.amount,
+        builder,
+    );
+
+    logger.log(&CustomEvent::Approval(ApprovalEvent {
+        owner: sender,
+        spender: params.spender,
+        token_id: params.token_id,
+        amount: params.amount,
+    }))?;
+    Ok(())
+}
+
+/// Register the public key the sender account uses to sign vouchers for
+/// `redeemVoucher`. Replaces any previously registered public key of the
+/// account.
+///
+/// It rejects if:
+/// - The sender is not an account.
+/// - It fails to parse the parameter.
+#[receive(
+    contract = "CIS2-Multi",
+    name = "registerPublicKey",
+    parameter = "PublicKeyEd25519",
+    error = "ContractError",
+    mutable
+)]
+fn contract_register_public_key<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<()> {
+    // Ensure the sender is an account.
+    let sender = if let Address::Account(account) = ctx.sender() {
+        account
+    } else {
+        bail!(CustomContractError::AccountOnly.into())
+    };
+    // Parse the parameter.
+    let public_key: PublicKeyEd25519 = ctx.parameter_cursor().get()?;
+    let _ = host.state_mut().public_keys.insert(sender, public_key);
+    Ok(())
+}
+
+/// Verify the signatures of the signer and execute the signed message on
+/// behalf of the signer, allowing a sponsor to pay for the transaction.
+/// The signatures are over the hash computed by `permit_message_hash`, also
+/// reported by `viewMessageHash`, and are checked against the keys of the
+/// signer account.
+/// The supported entrypoints are `transfer` and `updateOperator`.
+/// Logs a `Nonce` event and the events of the executed entrypoint.
+///
+/// It rejects if:
+/// - It fails to parse the parameter.
+/// - The nonce of the message is not the next nonce of the signer.
+/// - The message is intended for a different contract.
+/// - The message has expired.
+/// - The signer account does not exist.
+/// - The signature is invalid.
+/// - The entrypoint of the message is not supported.
+/// - It fails to parse the payload of the message.
+/// - Executing the entrypoint of the message rejects.
+/// - Fails to log event.
+#[receive(
+    contract = "CIS2-Multi",
+    name = "permit",
+    parameter = "PermitParam",
+    error = "ContractError",
+    crypto_primitives,
+    enable_logger,
+    mutable
+)]
+fn contract_permit<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+    crypto_primitives: &impl HasCryptoPrimitives,
+) -> ContractResult<()> {
+    // Parse the parameter.
+    let param: PermitParam = ctx.parameter_cursor().get()?;
+
+    // Update the nonce.
+    let nonce = {
+        let mut entry = host
+            .state_mut()
+            .nonces_registry
+            .entry(param.signer)
+            .or_insert(0);
+        let nonce = *entry;
+        *entry += 1;
+        nonce
+    };
+
+    let message = param.message;
+
+    // Check the nonce to prevent replay attacks.
+    ensure_eq!(
+        message.nonce,
+        nonce,
+        CustomContractError::NonceMismatch.into()
+    );
+
+    // Check that the signature was intended for this contract.
+    ensure_eq!(
+        message.contract_address,
+        ctx.self_address(),
+        CustomContractError::WrongContract.into()
+    );
+
+    // Check the signature has not expired.
+    ensure!(
+        message.timestamp > ctx.metadata().slot_time(),
+        CustomContractError::Expired.into()
+    );
+
+    // Check the signature.
+    let message_hash = permit_message_hash(&param.signer, &message, crypto_primitives);
+    let valid_signature = host
+        .check_account_signature(param.signer, &param.signature, &message_hash)
+        .map_err(|_| CustomContractError::MissingAccount)?;
+    ensure!(valid_signature, CustomContractError::WrongSignature.into());
+
+    let signer = Address::Account(param.signer);
+    if message.entry_point.as_entrypoint_name() == SUPPORTS_PERMIT_ENTRYPOINTS[0] {
+        // Execute the transfers on behalf of the signer.
+        let TransferParams(transfers): TransferParameter = from_bytes(&message.payload)?;
+        for transfer_entry in transfers {
+            transfer(
+                transfer_entry,
+                host,
+                logger,
+                signer,
+                ctx.metadata().slot_time(),
+            )?;
+        }
+    } else if message.entry_point.as_entrypoint_name() == SUPPORTS_PERMIT_ENTRYPOINTS[1] {
+        // Update the operators on behalf of the signer.
+        let UpdateOperatorParams(updates) = from_bytes(&message.payload)?;
+        let (state, builder) = host.state_and_builder();
+        for update in updates {
+            update_operator(
+                update.update,
+                signer,
+                update.operator,
+                None,
+                state,
+                builder,
+                logger,
+            )?;
+        }
+    } else {
+        bail!(CustomContractError::WrongEntryPoint.into())
+    }
+
+    // Log the nonce event.
+    logger.log(&CustomEvent::Nonce(NonceEvent {
+        account: param.signer,
+        nonce,
+    }))?;
+    Ok(())
+}
+
+/// Get the hash a signer signs for a `permit` message.
+///
+/// It rejects if:
+/// - It fails to parse the parameter.
+#[receive(
+    contract = "CIS2-Multi",
+    name = "viewMessageHash",
+    parameter = "ViewMessageHashParams",
+    return_value = "[u8; 32]",
+    error = "ContractError",
+    crypto_primitives
+)]
+fn contract_view_message_hash<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    _host: &impl HasHost<State<S>, StateApiType = S>,
+    crypto_primitives: &impl HasCryptoPrimitives,
+) -> ContractResult<[u8; 32]> {
+    // Parse the parameter.
+    let params: ViewMessageHashParams = ctx.parameter_cursor().get()?;
+    Ok(permit_message_hash(
+        &params.signer,
+        &params.message,
+        crypto_primitives,
+    ))
+}
+
+/// Get the next nonce of each of the given accounts, which must be used in the
+/// next `permit` message signed by the account.
+///
+/// It rejects if:
+/// - It fails to parse the parameter.
+#[receive(
+    contract = "CIS2-Multi",
+    name = "nonceOf",
+    parameter = "NonceOfQueryParams",
+    return_value = "NonceOfQueryResponse",
+    error = "ContractError"
+)]
+fn contract_nonce_of<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<NonceOfQueryResponse> {
+    // Parse the parameter.
+    let params: NonceOfQueryParams = ctx.parameter_cursor().get()?;
+    // Build the response.
+    let mut response = Vec::with_capacity(params.queries.len());
+    for account in params.queries {
+        let nonce = host
+            .state()
+            .nonces_registry
+            .get(&account)
+            .map_or(0, |nonce| *nonce);
+        response.push(nonce);
+    }
+    Ok(NonceOfQueryResponse(response))
+}
+
+/// Get whether the `permit` function supports each of the given entrypoints.
+///
+/// It rejects if:
+/// - It fails to parse the parameter.
+#[receive(
+    contract = "CIS2-Multi",
+    name = "supportsPermit",
+    parameter = "SupportsPermitQueryParams",
+    return_value = "SupportsQueryResponse",
+    error = "ContractError"
+)]
+fn contract_supports_permit<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    _host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<SupportsQueryResponse> {
+    // Parse the parameter.
+    let params: SupportsPermitQueryParams = ctx.parameter_cursor().get()?;
+    // Build the response.
+    let mut response = Vec::with_capacity(params.queries.len());
+    for entrypoint in params.queries {
+        if SUPPORTS_PERMIT_ENTRYPOINTS.contains(&entrypoint.as_entrypoint_name()) {
+            response.push(SupportResult::Support);
+        } else {
+            response.push(SupportResult::NoSupport);
+        }
+    }
+    Ok(SupportsQueryResponse::from(response))
+}
+
+/// Redeem a voucher signed by an account with the `Minter` role, minting the
+/// tokens of the voucher to the sender. The token type is created with the
+/// metadata and maximum supply of the voucher, if it is not minted yet. The
+/// price of the voucher is forwarded to the treasury account.
+/// Logs a `Mint` event and a `TokenMetadata` event if the token type is
+/// created.
+///
+/// It rejects if:
+/// - The contract is paused.
+/// - The sender is not an account.
+/// - Fails to parse parameter.
+/// - The voucher is intended for a different contract.
+/// - The voucher has expired.
+/// - The voucher has already been redeemed.
+/// - The signer does not have the `Minter` role.
+/// - The signer has not registered a public key.
+/// - The signature is invalid.
+/// - The CCD amount sent does not match the price of the voucher.
+/// - The hash of the token metadata is invalid.
+/// - The amount is above the maximum supply of the token type.
+/// - Fails to log event.
+/// - Fails to transfer the payment to the treasury.
+#[receive(
+    contract = "CIS2-Multi",
+    name = "redeemVoucher",
+    parameter = "RedeemVoucherParams",
+    error = "ContractError",
+    crypto_primitives,
+    enable_logger,
+    mutable,
+    payable
+)]
+fn contract_redeem_voucher<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    amount: Amount,
+    logger: &mut impl HasLogger,
+    crypto_primitives: &impl HasCryptoPrimitives,
+) -> ContractResult<()> {
+    ensure!(
+        !host.state().paused,
+        ContractError::Custom(CustomContractError::Paused)
+    );
+    // Only accounts can redeem vouchers, as the tokens are minted without
+    // invoking the `onReceivingCIS2` hook of a receiving contract.
+    let owner = if let Address::Account(account) = ctx.sender() {
+        Address::Account(account)
+    } else {
+        bail!(CustomContractError::AccountOnly.into())
+    };
+    // Parse the parameter.
+    let param: RedeemVoucherParams = ctx.parameter_cursor().get()?;
+    let voucher = param.voucher;
+
+    // Check that the voucher was intended for this contract.
+    ensure_eq!(
+        voucher.contract_address,
+        ctx.self_address(),
+        CustomContractError::WrongContract.into()
+    );
+
+    // Check the voucher has not expired.
+    ensure!(
+        voucher.expiry > ctx.metadata().slot_time(),
+        CustomContractError::Expired.into()
+    );
+
+    // Mark the voucher as redeemed to prevent replay attacks.
+    ensure!(
+        host.state_mut()
+            .redeemed_vouchers
+            .insert((param.signer, voucher.nonce)),
+        CustomContractError::VoucherAlreadyRedeemed.into()
+    );
+
+    // Check the signer is authorized to mint.
+    ensure!(
+        host.state()
+            .has_role(&Address::Account(param.signer), Role::Minter),
+        ContractError::Unauthorized
+    );
+
+    // Check the signature.
+    let public_key = host
+        .state()
+        .public_keys
+        .get(&param.signer)
+        .map(|public_key| *public_key)
+        .ok_or(CustomContractError::MissingPublicKey)?;
+    let voucher_hash = crypto_primitives
+        .hash_sha2_256(&voucher_message(&ctx.self_address(), &voucher))
+        .0;
+    ensure!(
+        crypto_primitives.verify_ed25519_signature(public_key, param.signature, &voucher_hash),
+        CustomContractError::WrongSignature.into()
+    );
+
+    ensure!(
+        amount == voucher.price,
+        ContractError::Custom(CustomContractError::IncorrectPayment)
+    );
+
+    let (state, builder) = host.state_and_builder();
+    let created = !state.contains_token(&voucher.token_id);
+    if created {
+        voucher.metadata.ensure_valid_hash()?;
+        state.create_token(
+            &voucher.token_id,
+            Some(&voucher.metadata),
+            voucher.max_supply,
+            false,
+            None,
+        );
+    }
+    state.mint(&voucher.token_id, voucher.amount, &owner, builder)?;
+
+    // Event for minted token.
+    logger.log(&Cis2Event::Mint(MintEvent {
+        token_id: voucher.token_id.to_owned(),
+        amount: voucher.amount,
+        owner,
+    }))?;
+
+    // Metadata URL for the token, if the token type was created.
+    if created {
+        logger.log(&Cis2Event::TokenMetadata::<_, ContractTokenAmount>(
+            TokenMetadataEvent {
+                token_id: voucher.token_id,
+                metadata_url: voucher.metadata.to_metadata_url(),
+            },
+        ))?;
+    }
+
+    // Forward the payment to the treasury.
+    let treasury = host.state().treasury;
+    host.invoke_transfer(&treasury, amount)
+        .map_err(|_| CustomContractError::InvokeTransferError)?;
+    Ok(())
+}
+
+/// Pause the contract, halting minting, burning and transferring tokens.
+/// Queries such as `balanceOf` are not affected.
+/// Can only be called by addresses with the `Pauser` role.
+/// Succeeds even if the contract is already paused.
+///
+/// It rejects if:
+/// - The sender does not have the `Pauser` role.
+#[receive(
+    contract = "CIS2-Multi",
+    name = "pause",
+    error = "ContractError",
+    mutable
+)]
+fn contract_pause<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<()> {
+    // Authorize the sender.
+    ensure!(
+        host.state().has_role(&ctx.sender(), Role::Pauser),
+        ContractError::Unauthorized
+    );
+    host.state_mut().paused = true;
+    Ok(())
+}
+
+/// Unpause the contract, resuming minting, burning and transferring tokens.
+/// Can only be called by addresses with the `Pauser` role.
+/// Succeeds even if the contract is not paused.
+///
+/// It rejects if:
+/// - The sender does not have the `Pauser` role.
+#[receive(
+    contract = "CIS2-Multi",
+    name = "unpause",
+    error = "ContractError",
+    mutable
+)]
+fn contract_unpause<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<()> {
+    // Authorize the sender.
+    ensure!(
+        host.state().has_role(&ctx.sender(), Role::Pauser),
+        ContractError::Unauthorized
+    );
+    host.state_mut().paused = false;
+    Ok(())
+}
+
+/// Propose a new admin of the contract, which takes over the `Admin` role of
+/// the current admin when it calls `acceptAdmin`. Proposing `None` cancels the
+/// proposal.
+/// Can only be called by the admin.
+/// Logs an `AdminTransferProposed` event.
+///
+/// It rejects if:
+/// - The sender is not the admin.
+/// - It fails to parse the parameter.
+/// - Fails to log event.
+#[receive(
+    contract = "CIS2-Multi",
+    name = "transferAdmin",
+    parameter = "Option<Address>",
+    error = "ContractError",
+    enable_logger,
+    mutable
+)]
+fn contract_transfer_admin<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    // Authorize the sender.
+    let sender = ctx.sender();
+    ensure!(host.state().admin == sender, ContractError::Unauthorized);
+    // Parse the parameter.
+    let pending_admin: Option<Address> = ctx.parameter_cursor().get()?;
+    host.state_mut().pending_admin = pending_admin;
+
+    logger.log(&CustomEvent::AdminTransferProposed(
+        AdminTransferProposedEvent {
+            admin: sender,
+            pending_admin,
+        },
+    ))?;
+    Ok(())
+}
+
+/// Accept becoming the admin of the contract, taking over the `Admin` role of
+/// the previous admin, together with the `Minter`, `MetadataUpdater` and
+/// `Pauser` roles the previous admin still holds.
+/// Can only be called by the address proposed with `transferAdmin`.
+/// Logs a `RoleRevoked` and a `RoleGranted` event for each role taken over and
+/// an `AdminChanged` event.
+///
+/// It rejects if:
+/// - The sender is not the proposed admin.
+/// - Fails to log event.
+#[receive(
+    contract = "CIS2-Multi",
+    name = "acceptAdmin",
+    error = "ContractError",
+    enable_logger,
+    mutable
+)]
+fn contract_accept_admin<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    let sender = ctx.sender();
+    let (previous_admin, moved_roles) = host.state_mut().accept_admin(&sender)?;
+
+    for role in [Role::Admin].iter().copied().chain(moved_roles) {
+        logger.log(&CustomEvent::RoleRevoked(RoleEvent {
+            address: previous_admin,
+            role,
+        }))?;
+        logger.log(&CustomEvent::RoleGranted(RoleEvent {
+            address: sender,
+            role,
+        }))?;
+    }
+    logger.log(&CustomEvent::AdminChanged(AdminChangedEvent {
+        previous_admin,
+        new_admin: sender,
+    }))?;
+    Ok(())
+}
+
+/// The response type for the contract function `viewAdmin`.
+#[derive(Debug, Serialize, SchemaType)]
+struct ViewAdminResponse {
+    /// The admin of the contract.
+    admin: Address,
+    /// The address proposed as the new admin, if any.
+    pending_admin: Option<Address>,
+}
+
+/// Get the admin of the contract and the proposed new admin.
+#[receive(
+    contract = "CIS2-Multi",
+    name = "viewAdmin",
+    return_value = "ViewAdminResponse",
+    error = "ContractError"
+)]
+fn contract_view_admin<S: HasStateApi>(
+    _ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<ViewAdminResponse> {
+    let state = host.state();
+    Ok(ViewAdminResponse {
+        admin: state.admin,
+        pending_admin: state.pending_admin,
+    })
+}
+
+/// Upgrade the contract to a new smart contract module, and optionally call an
+/// entrypoint of the new module, such as `migrate`, in the same transaction.
+/// Can only be called by addresses with the `Admin` role.
+///
+/// It rejects if:
+/// - The sender does not have the `Admin` role.
+/// - It fails to parse the parameter.
+/// - The upgrade fails, which could be if:
+///     - The module does not exist.
+///     - The module does not contain a contract with a matching name.
+///     - The smart contract version of the module is not supported.
+/// - The entrypoint called after the upgrade rejects.
+#[receive(
+    contract = "CIS2-Multi",
+    name = "upgrade",
+    parameter = "UpgradeParams",
+    error = "ContractError",
+    low_level
+)]
+fn contract_upgrade<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<S>,
+) -> ContractResult<()> {
+    // Read the top-level contract state. This function is low level, such that
+    // the state is not written back after the migration has updated it.
+    let state: State<S> = host.state().read_root()?;
+    // Authorize the sender.
+    ensure!(
+        state.has_role(&ctx.sender(), Role::Admin),
+        ContractError::Unauthorized
+    );
+    // Parse the parameter.
+    let params: UpgradeParams = ctx.parameter_cursor().get()?;
+    // Trigger the upgrade.
+    host.upgrade(params.module)
+        .map_err(CustomContractError::from)?;
+    // Call the entrypoint of the new module, if any.
+    if let Some((func, parameter)) = params.migrate {
+        host.invoke_contract_raw(
+            &ctx.self_address(),
+            parameter.as_parameter(),
+            func.as_entrypoint_name(),
+            Amount::zero(),
+        )?;
+    }
+    Ok(())
+}
+
+/// Migrate the state to the `STATE_VERSION` of this module, after upgrading
+/// from a module with an earlier state version. Does nothing if the state
+/// already has the `STATE_VERSION` of this module.
+/// Can only be called by the contract itself, as part of `upgrade`.
+///
+/// It rejects if:
+/// - The sender is not the contract itself.
+/// - The state version is not supported, such as the state version of a newer
+///   version of the contract.
+#[receive(
+    contract = "CIS2-Multi",
+    name = "migrate",
+    error = "ContractError",
+    low_level
+)]
+fn contract_migrate<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<S, StateApiType = S>,
+) -> ContractResult<()> {
+    ensure!(
+        ctx.sender() == Address::Contract(ctx.self_address()),
+        ContractError::Unauthorized
+    );
+    // The version is the first field of every state layout and determines the
+    // layout of the rest of the state. This function is low level, such that
+    // the state can be read in the layout of an earlier version.
+    let version: u32 = host.state().read_root()?;
+    match version {
+        STATE_VERSION => Ok(()),
+        1 => {
+            let state: StateV1<S> = host.state().read_root()?;
+            let state = state.migrate(host.state_builder());
+            host.state_mut().write_root(&state);
+            Ok(())
+        }
+        _ => bail!(CustomContractError::UnsupportedStateVersion.into()),
+    }
+}
+
+/// Get the version of the state layout.
+#[receive(
+    contract = "CIS2-Multi",
+    name = "stateVersion",
+    return_value = "u32",
+    error = "ContractError"
+)]
+fn contract_state_version<S: HasStateApi>(
+    _ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<u32> {
+    Ok(host.state().version)
+}
+
+/// Get whether the contract is paused.
+#[receive(
+    contract = "CIS2-Multi",
+    name = "isPaused",
+    return_value = "bool",
+    error = "ContractError"
+)]
+fn contract_is_paused<S: HasStateApi>(
+    _ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<bool> {
+    Ok(host.state().paused)
+}
+
+/// Grant a role to an address. The `Admin` role is moved with `transferAdmin`
+/// and `acceptAdmin` instead.
+/// Can only be called by addresses with the `Admin` role.
+/// Logs a `RoleGranted` event.
+///
+/// It rejects if:
+/// - The sender does not have the `Admin` role.
+/// - It fails to parse the parameter.
+/// - The role is `Admin`.
+/// - Fails to log event.
+#[receive(
+    contract = "CIS2-Multi",
+    name = "grantRole",
+    parameter = "RoleParams",
+    error = "ContractError",
+    enable_logger,
+    mutable
+)]
+fn contract_grant_role<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    // Authorize the sender.
+    ensure!(
+        host.state().has_role(&ctx.sender(), Role::Admin),
+        ContractError::Unauthorized
+    );
+    // Parse the parameter.
+    let params: RoleParams = ctx.parameter_cursor().get()?;
+    ensure!(
+        params.role != Role::Admin,
+        ContractError::Custom(CustomContractError::AdminRoleNotGrantable)
+    );
+    // Update the roles in the state.
+    host.state_mut().grant_role(&params.address, params.role);
+
+    logger.log(&CustomEvent::RoleGranted(RoleEvent {
+        address: params.address,
+        role: params.role,
+    }))?;
+    Ok(())
+}
+
+/// Revoke a role from an address. The `Admin` role is moved with `transferAdmin`
+/// and `acceptAdmin` instead.
+/// Can only be called by addresses with the `Admin` role.
+/// Logs a `RoleRevoked` event.
+///
+/// It rejects if:
+/// - The sender does not have the `Admin` role.
+/// - It fails to parse the parameter.
+/// - The role is `Admin`.
+/// - Fails to log event.
+#[receive(
+    contract = "CIS2-Multi",
+    name = "revokeRole",
+    parameter = "RoleParams",
+    error = "ContractError",
+    enable_logger,
+    mutable
+)]
+fn contract_revoke_role<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    // Authorize the sender.
+    ensure!(
+        host.state().has_role(&ctx.sender(), Role::Admin),
+        ContractError::Unauthorized
+    );
+    // Parse the parameter.
+    let params: RoleParams = ctx.parameter_cursor().get()?;
+    ensure!(
+        params.role != Role::Admin,
+        ContractError::Custom(CustomContractError::AdminRoleNotGrantable)
+    );
+    // Update the roles in the state.
+    host.state_mut().revoke_role(&params.address, params.role);
+
+    logger.log(&CustomEvent::RoleRevoked(RoleEvent {
+        address: params.address,
+        role: params.role,
+    }))?;
+    Ok(())
+}
+
+/// Parameter type for the contract function `hasRole`.
+#[derive(Serialize, SchemaType)]
+struct HasRoleQueryParams {
+    /// List of addresses and roles to query.
+    #[concordium(size_length = 2)]
+    queries: Vec<RoleParams>,
+}
+
+/// Response type for the contract function `hasRole`.
+/// The results are in the same order as the queries.
+#[derive(Debug, Serialize, SchemaType)]
+struct HasRoleQueryResponse(#[concordium(size_length = 2)] Vec<bool>);
+
+/// Takes a list of queries. Each query is an address and a role to check the
+/// address has been granted.
+///
+/// It rejects if:
+/// - It fails to parse the parameter.
+#[receive(
+    contract = "CIS2-Multi",
+    name = "hasRole",
+    parameter = "HasRoleQueryParams",
+    return_value = "HasRoleQueryResponse",
+    error = "ContractError"
+)]
+fn contract_has_role<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<HasRoleQueryResponse> {
+    // Parse the parameter.
+    let params: HasRoleQueryParams = ctx.parameter_cursor().get()?;
+    // Build the response.
+    let mut response = Vec::with_capacity(params.queries.len());
+    for query in params.queries {
+        response.push(host.state().has_role(&query.address, query.role));
+    }
+    Ok(HasRoleQueryResponse(response))
+}
+
+/// Parameter type for the CIS-2 function `balanceOf` specialized to the subset
+/// of TokenIDs used by this contract.
+type ContractBalanceOfQueryParams = BalanceOfQueryParams<ContractTokenId>;
+
+/// Response type for the CIS-2 function `balanceOf` specialized to the subset
+/// of TokenAmounts used by this contract.
+type ContractBalanceOfQueryResponse = BalanceOfQueryResponse<ContractTokenAmount>;
+
+/// Get the balance of given token IDs and addresses.
+///
+/// It rejects if:
+/// - It fails to parse the parameter.
+/// - Any of the queried `token_id` does not exist.
+#[receive(
+    contract = "CIS2-Multi",
+    name = "balanceOf",
+    parameter = "ContractBalanceOfQueryParams",
+    return_value = "ContractBalanceOfQueryResponse",
+    error = "ContractError"
+)]
+fn contract_balance_of<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<ContractBalanceOfQueryResponse> {
+    // Parse the parameter.
+    let params: ContractBalanceOfQueryParams = ctx.parameter_cursor().get()?;
+    // Build the response.
+    let mut response = Vec::with_capacity(params.queries.len());
+    for query in params.queries {
+        // Query the state for balance.
+        let amount = host.state().balance(&query.token_id, &query.address)?;
+        response.push(amount);
+    }
+    let result = ContractBalanceOfQueryResponse::from(response);
+    Ok(result)
+}
+
+/// Takes a list of queries. Each query is an owner address and some address to
+/// check as an operator of the owner address.
+///
+/// It rejects if:
+/// - It fails to parse the parameter.
+#[receive(
+    contract = "CIS2-Multi",
+    name = "operatorOf",
+    parameter = "OperatorOfQueryParams",
+    return_value = "OperatorOfQueryResponse",
+    error = "ContractError"
+)]
+fn contract_operator_of<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<OperatorOfQueryResponse> {
+    // Parse the parameter.
+    let params: OperatorOfQueryParams = ctx.parameter_cursor().get()?;
+    // Build the response.
+    let mut response = Vec::with_capacity(params.queries.len());
+    for query in params.queries {
+        // Query the state for address being an operator of owner.
+        let is_operator =
+            host.state()
+                .is_operator(&query.address, &query.owner, ctx.metadata().slot_time());
+        response.push(is_operator);
+    }
+    let result = OperatorOfQueryResponse::from(response);
+    Ok(result)
+}
+
+/// A query for the allowance of a spender for the tokens of an owner.
+#[derive(Serialize, SchemaType)]
+struct AllowanceOfQuery {
+    /// The owner of the tokens.
+    owner: Address,
+    /// The address allowed to transfer the tokens.
+    spender: Address,
+    /// The token type the allowance applies to.
+    token_id: ContractTokenId,
+}
+
+/// Parameter type for the contract function `allowanceOf`.
+#[derive(Serialize, SchemaType)]
+struct AllowanceOfQueryParams {
+    /// List of allowance queries.
+    #[concordium(size_length = 2)]
+    queries: Vec<AllowanceOfQuery>,
+}
+
+/// Response type for the contract function `allowanceOf`.
+/// The allowances are in the same order as the queries.
+#[derive(Debug, Serialize, SchemaType)]
+struct AllowanceOfQueryResponse(#[concordium(size_length = 2)] Vec<ContractTokenAmount>);
+
+/// Takes a list of queries. Each query is an owner address, a spender address
+/// and a token ID, for which the remaining allowance is returned.
+///
+/// It rejects if:
+/// - It fails to parse the parameter.
+#[receive(
+    contract = "CIS2-Multi",
+    name = "allowanceOf",
+    parameter = "AllowanceOfQueryParams",
+    return_value = "AllowanceOfQueryResponse",
+    error = "ContractError"
+)]
+fn contract_allowance_of<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<AllowanceOfQueryResponse> {
+    // Parse the parameter.
+    let params: AllowanceOfQueryParams = ctx.parameter_cursor().get()?;
+    // Build the response.
+    let mut response = Vec::with_capacity(params.queries.len());
+    for query in params.queries {
+        response.push(
+            host.state()
+                .allowance(&query.owner, &query.spender, &query.token_id),
+        );
+    }
+    Ok(AllowanceOfQueryResponse(response))
+}
+
+/// Parameter type for the CIS-2 function `tokenMetadata` specialized to the
+/// subset of TokenIDs used by this contract.
+type ContractTokenMetadataQueryParams = TokenMetadataQueryParams<ContractTokenId>;
+
+/// Get the token metadata URLs and checksums given a list of token IDs.
+///
+/// It rejects if:
+/// - It fails to parse the parameter.
+/// - Any of the queried `token_id` does not exist.
+#[receive(
+    contract = "CIS2-Multi",
+    name = "tokenMetadata",
+    parameter = "ContractTokenMetadataQueryParams",
+    return_value = "TokenMetadataQueryResponse",
+    error = "ContractError"
+)]
+fn contract_token_metadata<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<TokenMetadataQueryResponse> {
+    // Parse the parameter.
+    let params: ContractTokenMetadataQueryParams = ctx.parameter_cursor().get()?;
+    // Build the response.
+    let mut response = Vec::with_capacity(params.queries.len());
+    for token_id in params.queries {
+        let metadata_url: MetadataUrl = host.state().metadata_url(&token_id)?;
+
+        response.push(metadata_url);
+    }
+    let result = TokenMetadataQueryResponse::from(response);
+    Ok(result)
+}
+
+/// Parameter type for the contract function `totalSupply`.
+#[derive(Serialize, SchemaType)]
+struct TotalSupplyQueryParams {
+    /// List of token IDs to query the total supply of.
+    #[concordium(size_length = 2)]
+    queries: Vec<ContractTokenId>,
+}
+
+/// Response type for the contract function `totalSupply`.
+/// The total supplies are in the same order as the queried token IDs.
+#[derive(Debug, Serialize, SchemaType)]
+struct TotalSupplyQueryResponse(#[concordium(size_length = 2)] Vec<ContractTokenAmount>);
+
+/// Get the total supply of each of the given token IDs.
+///
+/// It rejects if:
+/// - It fails to parse the parameter.
+/// - Any of the queried `token_id` does not exist.
+#[receive(
+    contract = "CIS2-Multi",
+    name = "totalSupply",
+    parameter = "TotalSupplyQueryParams",
+    return_value = "TotalSupplyQueryResponse",
+    error = "ContractError"
+)]
+fn contract_total_supply<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<TotalSupplyQueryResponse> {
+    // Parse the parameter.
+    let params: TotalSupplyQueryParams = ctx.parameter_cursor().get()?;
+    // Build the response.
+    let mut response = Vec::with_capacity(params.queries.len());
+    for token_id in params.queries {
+        let supply = host.state().total_supply(&token_id)?;
+        response.push(supply);
+    }
+    Ok(TotalSupplyQueryResponse(response))
+}
+
+/// Parameter type for the contract function `royaltyInfo`.
+#[derive(Serialize, SchemaType)]
+struct RoyaltyInfoParams {
+    /// The token type being sold.
+    token_id: ContractTokenId,
+    /// The sale price.
+    sale_price: Amount,
+}
+
+/// Response type for the contract function `royaltyInfo`.
+#[derive(Debug, Serialize, SchemaType)]
+struct RoyaltyInfoResponse {
+    /// The recipients of the royalties and the amount owed to each of them.
+    #[concordium(size_length = 2)]
+    payments: Vec<(AccountAddress, Amount)>,
+}
+
+/// Get the royalty payments owed when a token of a given token type is sold
+/// for a given price.
+///
+/// It rejects if:
+/// - It fails to parse the parameter.
+/// - The `token_id` does not exist.
+#[receive(
+    contract = "CIS2-Multi",
+    name = "royaltyInfo",
+    parameter = "RoyaltyInfoParams",
+    return_value = "RoyaltyInfoResponse",
+    error = "ContractError"
+)]
+fn contract_royalty_info<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<RoyaltyInfoResponse> {
+    // Parse the parameter.
+    let params: RoyaltyInfoParams = ctx.parameter_cursor().get()?;
+    let payments = host
+        .state()
+        .royalties(&params.token_id)?
+        .iter()
+        .map(|royalty| (royalty.recipient, royalty.payment(params.sale_price)))
+        .collect();
+    Ok(RoyaltyInfoResponse { payments })
+}
+
+/// The parameter of `onReceivingCIS2`, accepting the token IDs of any CIS2
+/// contract.
+type OnReceivingParams = OnReceivingCis2Params<TokenIdVec, ContractTokenAmount>;
+
+/// Receive CIS2 tokens of another contract, according to the `ReceiveMode` set
+/// at initialization. In `Custody` mode, the tokens are kept for the `from`
+/// address of the transfer, which can withdraw them with `withdraw`, and a
+/// `Deposit` event is logged. In `ForwardToAdmin` mode, the tokens are
+/// transferred to the admin and a `Forward` event is logged. An admin which is
+/// a contract is sent the tokens through its `onReceivingCIS2` entrypoint.
+///
+/// Note: The name of this function is not part the CIS2, and a contract can
+/// have multiple functions for receiving tokens.
+///
+/// It rejects if:
+/// - Sender is not a contract.
+/// - It fails to parse the parameter.
+/// - The receive mode is `Reject`.
+/// - Calling `transfer` of the sender contract rejects, when forwarding, which
+///   includes an admin contract without an `onReceivingCIS2` entrypoint.
+/// - Fails to log event.
+#[receive(
+    contract = "CIS2-Multi",
+    name = "onReceivingCIS2",
+    parameter = "OnReceivingParams",
+    error = "ContractError",
+    enable_logger,
+    mutable
+)]
+fn contract_on_cis2_received<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    // Ensure the sender is a contract.
+    let sender = if let Address::Contract(contract) = ctx.sender() {
+        contract
+    } else {
+        bail!(CustomContractError::ContractOnly.into())
+    };
+
+    // Parse the parameter.
+    let params: OnReceivingParams = ctx.parameter_cursor().get()?;
+
+    match host.state().receive_mode {
+        ReceiveMode::Reject => bail!(CustomContractError::ReceiveRejected.into()),
+        ReceiveMode::Custody => {
+            let deposit = DepositKey {
+                token_contract: sender,
+                token_id: params.token_id,
+                depositor: params.from,
+            };
+            host.state_mut().deposit(deposit.clone(), params.amount);
+            logger.log(&CustomEvent::Deposit(DepositEvent {
+                deposit,
+                amount: params.amount,
+            }))?;
+        }
+        ReceiveMode::ForwardToAdmin => {
+            let admin = host.state().admin;
+            let to = match admin {
+                Address::Account(account) => Receiver::from_account(account),
+                Address::Contract(contract) => Receiver::from_contract(
+                    contract,
+                    OwnedEntrypointName::new_unchecked("onReceivingCIS2".to_string()),
+                ),
+            };
+            let transfer = Transfer {
+                token_id: params.token_id.clone(),
+                amount: params.amount,
+                from: Address::Contract(ctx.self_address()),
+                to,
+                data: AdditionalData::empty(),
+            };
+            host.invoke_contract(
+                &sender,
+                &TransferParams::from(vec![transfer]),
+                EntrypointName::new("transfer")?,
+                Amount::zero(),
+            )?;
+            logger.log(&CustomEvent::Forward(ForwardEvent {
+                token_contract: sender,
+                token_id: params.token_id,
+                amount: params.amount,
+                to: admin,
+            }))?;
+        }
+    }
+    Ok(())
+}
+
+/// Withdraw CIS2 tokens of another contract kept in custody for the sender.
+/// Logs a `Withdraw` event.
+///
+/// It rejects if:
+/// - It fails to parse the parameter.
+/// - The sender has insufficient tokens of the token ID in custody.
+/// - Fails to log event.
+/// - Calling `transfer` of the token contract rejects.
+#[receive(
+    contract = "CIS2-Multi",
+    name = "withdraw",
+    parameter = "WithdrawParams",
+    error = "ContractError",
+    enable_logger,
+    mutable
+)]
+fn contract_withdraw<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    // Parse the parameter.
+    let params: WithdrawParams = ctx.parameter_cursor().get()?;
+    let deposit = DepositKey {
+        token_contract: params.token_contract,
+        token_id: params.token_id,
+        depositor: ctx.sender(),
+    };
+    // Update the state before calling the token contract.
+    host.state_mut().withdraw(&deposit, params.amount)?;
+    logger.log(&CustomEvent::Withdraw(WithdrawEvent {
+        deposit: deposit.clone(),
+        amount: params.amount,
+        to: params.to.address(),
+    }))?;
+
+    let transfer = Transfer {
+        token_id: deposit.token_id,
+        amount: params.amount,
+        from: Address::Contract(ctx.self_address()),
+        to: params.to,
+        data: AdditionalData::empty(),
+    };
+    host.invoke_contract(
+        &params.token_contract,
+        &TransferParams::from(vec![transfer]),
+        EntrypointName::new("transfer")?,
+        Amount::zero(),
+    )?;
+    Ok(())
+}
+
+/// Parameter type for the contract function `depositOf`.
+#[derive(Serialize, SchemaType)]
+struct DepositOfQueryParams {
+    /// List of deposits to query.
+    #[concordium(size_length = 2)]
+    queries: Vec<DepositKey>,
+}
+
+/// Response type for the contract function `depositOf`.
+/// The amounts are in the same order as the queries.
+#[derive(Debug, Serialize, SchemaType)]
+struct DepositOfQueryResponse(#[concordium(size_length = 2)] Vec<ContractTokenAmount>);
+
+/// Get the amount of CIS2 tokens of other contracts kept in custody for each
+/// of the given depositors.
+///
+/// It rejects if:
+/// - It fails to parse the parameter.
+#[receive(
+    contract = "CIS2-Multi",
+    name = "depositOf",
+    parameter = "DepositOfQueryParams",
+    return_value = "DepositOfQueryResponse",
+    error = "ContractError"
+)]
+fn contract_deposit_of<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<DepositOfQueryResponse> {
+    // Parse the parameter.
+    let params: DepositOfQueryParams = ctx.parameter_cursor().get()?;
+    let response = params
+        .queries
+        .iter()
+        .map(|deposit| host.state().deposit_of(deposit))
+        .collect();
+    Ok(DepositOfQueryResponse(response))
+}
+
+/// Get the supported standards or addresses for a implementation given list of
+/// standard identifiers.
+///
+/// It rejects if:
+/// - It fails to parse the parameter.
+#[receive(
+    contract = "CIS2-Multi",
+    name = "supports",
+    parameter = "SupportsQueryParams",
+    return_value = "SupportsQueryResponse",
+    error = "ContractError"
+)]
+fn contract_supports<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<SupportsQueryResponse> {
+    // Parse the parameter.
+    let params: SupportsQueryParams = ctx.parameter_cursor().get()?;
+
+    // Build the response.
+    let mut response = Vec::with_capacity(params.queries.len());
+    for std_id in params.queries {
+        if SUPPORTS_STANDARDS.contains(&std_id.as_standard_identifier()) {
+            response.push(SupportResult::Support);
+        } else {
+            response.push(host.state().have_implementors(&std_id));
+        }
+    }
+    let result = SupportsQueryResponse::from(response);
+    Ok(result)
+}
+
+/// Set the addresses for an implementation given a standard identifier and a
+/// list of contract addresses.
+///
+/// It rejects if:
+/// - The sender does not have the `Admin` role.
+/// - It fails to parse the parameter.
+#[receive(
+    contract = "CIS2-Multi",
+    name = "setImplementors",
+    parameter = "SetImplementorsParams",
+    error = "ContractError",
+    mutable
+)]
+fn contract_set_implementor<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<()> {
+    // Authorize the sender.
+    ensure!(
+        host.state().has_role(&ctx.sender(), Role::Admin),
+        ContractError::Unauthorized
+    );
+    // Parse the parameter.
+    let params: SetImplementorsParams = ctx.parameter_cursor().get()?;
+    // Update the implementors in the state
+    host.state_mut()
+        .set_implementors(params.id, params.implementors);
+    Ok(())
+}
+
+// Tests
+
+// The unit tests use the test infrastructure of `concordium-std`, which is
+// deprecated in favor of `concordium-smart-contract-testing`, used by the
+// integration tests.
+#[concordium_cfg_test]
+#[allow(deprecated)]
+mod tests {
+    use super::*;
+    use test_infrastructure::*;
+
+    const ACCOUNT_0: AccountAddress = AccountAddress([0u8; 32]);
+    const ADDRESS_0: Address = Address::Account(ACCOUNT_0);
+    const ACCOUNT_1: AccountAddress = AccountAddress([1u8; 32]);
+    const ADDRESS_1: Address = Address::Account(ACCOUNT_1);
+    /// The hash of the metadata of the tokens used in the tests.
+    const METADATA_HASH: [u8; 32] = [
+        0xdb, 0x2c, 0xa4, 0x20, 0xa0, 0x09, 0x05, 0x93, 0xac, 0x65, 0x59, 0xff, 0x2a, 0x98, 0xce,
+        0x30, 0xab, 0xfe, 0x66, 0x5d, 0x7a, 0x18, 0xff, 0x3c, 0x63, 0x88, 0x3e, 0x8b, 0x98, 0x62,
+        0x2a, 0x73,
+    ];
+
+    /// The token ID of the first token used in the tests.
+    fn token_0() -> ContractTokenId {
+        new_token_id(2)
+    }
+
+    /// The token ID of the second token used in the tests.
+    fn token_1() -> ContractTokenId {
+        new_token_id(42)
+    }
+
+    /// Test helper function which creates a contract state with two tokens with
+    /// id `token_0()` and id `token_1()` owned by `ADDRESS_0`, and `ADDRESS_0`
+    /// the admin and granted the `Minter` and `MetadataUpdater` roles.
+    fn initial_state<S: HasStateApi>(state_builder: &mut StateBuilder<S>) -> State<S> {
+        let mut state = State::empty(
+            state_builder,
+            CollectionInfo::default(),
+            ADDRESS_0,
+            ACCOUNT_0,
+            None,
+        );
+        state.grant_role(&ADDRESS_0, Role::Minter);
+        state.grant_role(&ADDRESS_0, Role::MetadataUpdater);
+        state.create_token(
+            &token_0(),
+            Some(&{
+                let url = "url".to_owned();
+                let hash = Some(METADATA_HASH);
+                TokenMetadata { url, hash }
+            }),
+            1000.into(),
+            false,
+            None,
+        );
+        state
+            .mint(&token_0(), 400.into(), &ADDRESS_0, state_builder)
+            .expect_report("Minting TOKEN_0 is expected to succeed");
+        state.create_token(
+            &token_1(),
+            Some(&{
+                let url = "url".to_owned();
+                let hash = Some(METADATA_HASH);
+                TokenMetadata { url, hash }
+            }),
+            1.into(),
+            false,
+            None,
+        );
+        state
+            .mint(&token_1(), 1.into(), &ADDRESS_0, state_builder)
+            .expect_report("Minting TOKEN_1 is expected to succeed");
+        state
+    }
+
+    /// Test initialization succeeds with a state with no tokens.
+    #[concordium_test]
+    fn test_init() {
+        // Setup the context
+        let mut ctx = TestInitContext::empty();
+        ctx.set_init_origin(ACCOUNT_0);
+        let parameter_bytes = to_bytes(&InitParams {
+            name: "Collection".to_string(),
+            symbol: "COL".to_string(),
+            metadata: None,
+            admin: None,
+            base_url: None,
+            royalties: Vec::new(),
+            receive_mode: ReceiveMode::Reject,
+        });
+        ctx.set_parameter(&parameter_bytes);
+        let mut builder = TestStateBuilder::new();
+
+        // Call the contract function.
+        let result = contract_init(&ctx, &mut builder);
+
+        // Check the result
+        let state = result.expect_report("Contract initialization failed");
+
+        // Check the state
+        claim_eq!(
+            state.tokens.iter().count(),
+            0,
+            "Only one token is initialized"
+        );
+        claim!(
+            state.has_role(&ADDRESS_0, Role::Admin) && state.has_role(&ADDRESS_0, Role::Minter),
+            "The instantiating account should be granted the roles"
+        );
+        claim_eq!(
+            state.collection.name,
+            "Collection".to_string(),
+            "The collection name should be stored"
+        );
+    }
+
+    /// Test initialization with a separate admin grants the roles to the admin
+    /// and stores the collection information reported by `collectionInfo`.
+    #[concordium_test]
+    fn test_init_with_admin() {
+        // Setup the context
+        let mut ctx = TestInitContext::empty();
+        ctx.set_init_origin(ACCOUNT_0);
+        let parameter_bytes = to_bytes(&InitParams {
+            name: "Collection".to_string(),
+            symbol: "COL".to_string(),
+            metadata: Some(TokenMetadata {
+                url: "https://example.com/collection.json".to_string(),
+                hash: Some(METADATA_HASH),
+            }),
+            admin: Some(ADDRESS_1),
+            base_url: None,
+            royalties: Vec::new(),
+            receive_mode: ReceiveMode::Reject,
+        });
+        ctx.set_parameter(&parameter_bytes);
+        let mut builder = TestStateBuilder::new();
+
+        // Call the contract function.
+        let result = contract_init(&ctx, &mut builder);
+
+        // Check the state
+        let state = result.expect_report("Contract initialization failed");
+        claim!(
+            state.has_role(&ADDRESS_1, Role::Admin) && state.has_role(&ADDRESS_1, Role::Pauser),
+            "The admin should be granted the roles"
+        );
+        claim!(
+            !state.has_role(&ADDRESS_0, Role::Admin),
+            "The instantiating account should not be granted the roles"
+        );
+        claim_eq!(
+            state.treasury,
+            ACCOUNT_1,
+            "The admin should be the treasury"
+        );
+
+        // Query the collection information.
+        let host = TestHost::new(state, builder);
+        let ctx = TestReceiveContext::empty();
+        let result: ContractResult<CollectionInfo> = contract_collection_info(&ctx, &host);
+        let info = result.expect_report("Failed getting result value");
+        claim_eq!(info.symbol, "COL".to_string(), "Symbol should be reported");
+        claim_eq!(
+            info.metadata_url.map(|metadata_url| metadata_url.url),
+            Some("https://example.com/collection.json".to_string()),
+            "Metadata URL should be reported"
+        );
+    }
+
+    /// Test minting succeeds and the tokens are owned by the given address and
+    /// the appropriate events are logged.
+    #[concordium_test]
+    fn test_mint() {
+        // Setup the context
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(ADDRESS_0);
+        ctx.set_owner(ACCOUNT_0);
+
+        // and parameter.
+        let mut tokens = collections::BTreeMap::new();
+        tokens.insert(
+            token_0(),
+            TokenMintParams {
+                metadata: Some({
+                    let url = "url".to_owned();
+                    let hash = Some(METADATA_HASH);
+                    TokenMetadata { url, hash }
+                }),
+                max_supply: 1000.into(),
+                amount: 400.into(),
+                soulbound: false,
+                royalties: None,
+            },
+        );
+        tokens.insert(
+            token_1(),
+            TokenMintParams {
+                metadata: Some({
                     let url = "url".to_owned();
-                    let hash = "db2ca420a0090593ac6559ff2a98ce30abfe665d7a18ff3c63883e8b98622a73"
-                        .to_owned();
+                    let hash = Some(METADATA_HASH);
                     TokenMetadata { url, hash }
+                }),
+                max_supply: 1.into(),
+                amount: 1.into(),
+                soulbound: false,
+                royalties: None,
+            },
+        );
+        let parameter = MintParams {
+            owner: ADDRESS_0,
+            tokens,
+        };
+        let parameter_bytes = to_bytes(&parameter);
+        ctx.set_parameter(&parameter_bytes);
+
+        let mut logger = TestLogger::init();
+        let mut state_builder = TestStateBuilder::new();
+        let mut state = State::empty(
+            &mut state_builder,
+            CollectionInfo::default(),
+            ADDRESS_0,
+            ACCOUNT_0,
+            None,
+        );
+        state.grant_role(&ADDRESS_0, Role::Minter);
+        let mut host = TestHost::new(state, state_builder);
+
+        // Call the contract function.
+        let result: ContractResult<()> = contract_mint(&ctx, &mut host, &mut logger);
+
+        // Check the result
+        claim!(result.is_ok(), "Results in rejection");
+
+        // Check the state
+        claim_eq!(
+            host.state().tokens.iter().count(),
+            2,
+            "Only one token is initialized"
+        );
+        let balance0 = host
+            .state()
+            .balance(&token_0(), &ADDRESS_0)
+            .expect_report("Token is expected to exist");
+        claim_eq!(
+            balance0,
+            400.into(),
+            "Initial tokens are owned by the contract instantiater"
+        );
+
+        let balance1 = host
+            .state()
+            .balance(&token_1(), &ADDRESS_0)
+            .expect_report("Token is expected to exist");
+        // unsafe {
+        claim_eq!(
+            balance1,
+            1.into(),
+            "Initial tokens are owned by the contract instantiater"
+        );
+
+        // Check the logs
+        claim_eq!(logger.logs.len(), 4, "Exactly four events should be logged");
+        claim!(
+            logger.logs.contains(&to_bytes(&Cis2Event::Mint(MintEvent {
+                owner: ADDRESS_0,
+                token_id: token_0(),
+                amount: ContractTokenAmount::from(400),
+            }))),
+            "Expected an event for minting TOKEN_0"
+        );
+        claim!(
+            logger.logs.contains(&to_bytes(&Cis2Event::Mint(MintEvent {
+                owner: ADDRESS_0,
+                token_id: token_1(),
+                amount: ContractTokenAmount::from(1),
+            }))),
+            "Expected an event for minting TOKEN_1"
+        );
+
+        claim!(
+            logger.logs.contains(&to_bytes(
+                &Cis2Event::TokenMetadata::<_, ContractTokenAmount>(TokenMetadataEvent {
+                    token_id: token_0(),
+                    metadata_url: (TokenMetadata {
+                        url: "url".to_string(),
+                        hash: Some(METADATA_HASH)
+                    })
+                    .to_metadata_url(),
+                })
+            )),
+            "Expected an event for token metadata for TOKEN_0"
+        );
+
+        claim!(
+            logger.logs.contains(&to_bytes(
+                &Cis2Event::TokenMetadata::<_, ContractTokenAmount>(TokenMetadataEvent {
+                    token_id: token_1(),
+                    metadata_url: (TokenMetadata {
+                        url: "url".to_string(),
+                        hash: Some(METADATA_HASH)
+                    })
+                    .to_metadata_url(),
+                })
+            )),
+            "Expected an event for token metadata for TOKEN_1"
+        );
+        // }
+    }
+
+    /// Test transfer succeeds, when `from` is the sender.
+    #[concordium_test]
+    fn test_transfer_account() {
+        // Setup the context
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(0));
+        ctx.set_sender(ADDRESS_0);
+
+        // and parameter.
+        let transfer = Transfer {
+            token_id: token_0(),
+            amount: ContractTokenAmount::from(100),
+            from: ADDRESS_0,
+            to: Receiver::from_account(ACCOUNT_1),
+            data: AdditionalData::empty(),
+        };
+        let parameter = TransferParams::from(vec![transfer]);
+        let parameter_bytes = to_bytes(&parameter);
+        ctx.set_parameter(&parameter_bytes);
+
+        let mut logger = TestLogger::init();
+        let mut state_builder = TestStateBuilder::new();
+        let state = initial_state(&mut state_builder);
+        let mut host = TestHost::new(state, state_builder);
+
+        // Call the contract function.
+        let result: ContractResult<()> = contract_transfer(&ctx, &mut host, &mut logger);
+        // Check the result.
+        claim!(result.is_ok(), "Results in rejection");
+
+        // Check the state.
+        let balance0 = host
+            .state()
+            .balance(&token_0(), &ADDRESS_0)
+            .expect_report("Token is expected to exist");
+        let balance1 = host
+            .state()
+            .balance(&token_0(), &ADDRESS_1)
+            .expect_report("Token is expected to exist");
+        claim_eq!(
+            balance0,
+            300.into(),
+            "Token owner balance should be decreased by the transferred amount."
+        );
+        claim_eq!(
+            balance1,
+            100.into(),
+            "Token receiver balance should be increased by the transferred amount"
+        );
+
+        // Check the logs.
+        claim_eq!(logger.logs.len(), 1, "Only one event should be logged");
+        claim_eq!(
+            logger.logs[0],
+            to_bytes(&Cis2Event::Transfer(TransferEvent {
+                from: ADDRESS_0,
+                to: ADDRESS_1,
+                token_id: token_0(),
+                amount: ContractTokenAmount::from(100),
+            })),
+            "Incorrect event emitted"
+        )
+    }
+
+    /// Test transfer token fails, when sender is neither the owner or an
+    /// operator of the owner.
+    #[concordium_test]
+    fn test_transfer_not_authorized() {
+        // Setup the context
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(0));
+        ctx.set_sender(ADDRESS_1);
+
+        // and parameter.
+        let transfer = Transfer {
+            from: ADDRESS_0,
+            to: Receiver::from_account(ACCOUNT_1),
+            token_id: token_0(),
+            amount: ContractTokenAmount::from(100),
+            data: AdditionalData::empty(),
+        };
+        let parameter = TransferParams::from(vec![transfer]);
+        let parameter_bytes = to_bytes(&parameter);
+        ctx.set_parameter(&parameter_bytes);
+
+        let mut logger = TestLogger::init();
+        let mut state_builder = TestStateBuilder::new();
+        let state = initial_state(&mut state_builder);
+        let mut host = TestHost::new(state, state_builder);
+
+        // Call the contract function.
+        let result: ContractResult<()> = contract_transfer(&ctx, &mut host, &mut logger);
+        // Check the result.
+        let err = result.expect_err_report("Expected to fail");
+        claim_eq!(
+            err,
+            ContractError::Unauthorized,
+            "Error is expected to be Unauthorized"
+        )
+    }
+
+    /// Test transfer succeeds when sender is not the owner, but is an operator
+    /// of the owner.
+    #[concordium_test]
+    fn test_operator_transfer() {
+        // Setup the context
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(0));
+        ctx.set_sender(ADDRESS_1);
+
+        // and parameter.
+        let transfer = Transfer {
+            from: ADDRESS_0,
+            to: Receiver::from_account(ACCOUNT_1),
+            token_id: token_0(),
+            amount: ContractTokenAmount::from(100),
+            data: AdditionalData::empty(),
+        };
+        let parameter = TransferParams::from(vec![transfer]);
+        let parameter_bytes = to_bytes(&parameter);
+        ctx.set_parameter(&parameter_bytes);
+
+        let mut logger = TestLogger::init();
+        let mut state_builder = TestStateBuilder::new();
+        let mut state = initial_state(&mut state_builder);
+        state.add_operator(&ADDRESS_0, &ADDRESS_1, None, &mut state_builder);
+        let mut host = TestHost::new(state, state_builder);
+
+        // Call the contract function.
+        let result: ContractResult<()> = contract_transfer(&ctx, &mut host, &mut logger);
+
+        // Check the result.
+        claim!(result.is_ok(), "Results in rejection");
+
+        // Check the state.
+        let balance0 = host
+            .state()
+            .balance(&token_0(), &ADDRESS_0)
+            .expect_report("Token is expected to exist");
+        let balance1 = host
+            .state()
+            .balance(&token_0(), &ADDRESS_1)
+            .expect_report("Token is expected to exist");
+        claim_eq!(
+            balance0,
+            300.into(),
+            "Token owner balance should be decreased by the transferred amount"
+        );
+        claim_eq!(
+            balance1,
+            100.into(),
+            "Token receiver balance should be increased by the transferred amount"
+        );
+
+        // Check the logs.
+        claim_eq!(logger.logs.len(), 1, "Only one event should be logged");
+        claim_eq!(
+            logger.logs[0],
+            to_bytes(&Cis2Event::Transfer(TransferEvent {
+                from: ADDRESS_0,
+                to: ADDRESS_1,
+                token_id: token_0(),
+                amount: ContractTokenAmount::from(100),
+            })),
+            "Incorrect event emitted"
+        )
+    }
+
+    /// Test adding an operator succeeds and the appropriate event is logged.
+    #[concordium_test]
+    fn test_add_operator() {
+        // Setup the context
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(0));
+        ctx.set_sender(ADDRESS_0);
+
+        // and parameter.
+        let update = UpdateOperator {
+            operator: ADDRESS_1,
+            update: OperatorUpdate::Add,
+        };
+        let parameter = UpdateOperatorParams(vec![update]);
+        let parameter_bytes = to_bytes(&parameter);
+        ctx.set_parameter(&parameter_bytes);
+
+        let mut logger = TestLogger::init();
+        let mut state_builder = TestStateBuilder::new();
+        let state = initial_state(&mut state_builder);
+        let mut host = TestHost::new(state, state_builder);
+
+        // Call the contract function.
+        let result: ContractResult<()> = contract_update_operator(&ctx, &mut host, &mut logger);
+
+        // Check the result.
+        claim!(result.is_ok(), "Results in rejection");
+
+        // Check the state.
+        let is_operator =
+            host.state()
+                .is_operator(&ADDRESS_1, &ADDRESS_0, Timestamp::from_timestamp_millis(0));
+        claim!(is_operator, "Account should be an operator");
+
+        // Checking that `ADDRESS_1` is an operator in the query response of the
+        // `contract_operator_of` function as well.
+        // Setup parameter.
+        let operator_of_query = OperatorOfQuery {
+            address: ADDRESS_1,
+            owner: ADDRESS_0,
+        };
+
+        let operator_of_query_vector = OperatorOfQueryParams {
+            queries: vec![operator_of_query],
+        };
+        let parameter_bytes = to_bytes(&operator_of_query_vector);
+
+        ctx.set_parameter(&parameter_bytes);
+
+        // Checking the return value of the `contract_operator_of` function
+        let result: ContractResult<OperatorOfQueryResponse> = contract_operator_of(&ctx, &host);
+
+        claim_eq!(
+            result.expect_report("Failed getting result value").0,
+            [true],
+            "Account should be an operator in the query response"
+        );
+
+        // Check the logs.
+        claim_eq!(logger.logs.len(), 1, "One event should be logged");
+        claim_eq!(
+            logger.logs[0],
+            to_bytes(
+                &Cis2Event::<ContractTokenId, ContractTokenAmount>::UpdateOperator(
+                    UpdateOperatorEvent {
+                        owner: ADDRESS_0,
+                        operator: ADDRESS_1,
+                        update: OperatorUpdate::Add,
+                    }
+                )
+            ),
+            "Incorrect event emitted"
+        )
+    }
+
+    /// Test burning succeeds when the sender is the owner, the balance is
+    /// decreased and a `Burn` event is logged.
+    #[concordium_test]
+    fn test_burn() {
+        // Setup the context
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(0));
+        ctx.set_sender(ADDRESS_0);
+
+        // and parameter.
+        let parameter = BurnParams {
+            owner: ADDRESS_0,
+            token_id: token_0(),
+            amount: ContractTokenAmount::from(100),
+        };
+        let parameter_bytes = to_bytes(&parameter);
+        ctx.set_parameter(&parameter_bytes);
+
+        let mut logger = TestLogger::init();
+        let mut state_builder = TestStateBuilder::new();
+        let state = initial_state(&mut state_builder);
+        let mut host = TestHost::new(state, state_builder);
+
+        // Call the contract function.
+        let result: ContractResult<()> = contract_burn(&ctx, &mut host, &mut logger);
+
+        // Check the result.
+        claim!(result.is_ok(), "Results in rejection");
+
+        // Check the state.
+        let balance0 = host
+            .state()
+            .balance(&token_0(), &ADDRESS_0)
+            .expect_report("Token is expected to exist");
+        claim_eq!(
+            balance0,
+            300.into(),
+            "Token owner balance should be decreased by the burned amount"
+        );
+
+        // Check the logs.
+        claim_eq!(logger.logs.len(), 1, "Only one event should be logged");
+        claim_eq!(
+            logger.logs[0],
+            to_bytes(&Cis2Event::Burn(BurnEvent {
+                owner: ADDRESS_0,
+                token_id: token_0(),
+                amount: ContractTokenAmount::from(100),
+            })),
+            "Incorrect event emitted"
+        )
+    }
+
+    /// Test burning fails, when sender is neither the owner or an operator of
+    /// the owner.
+    #[concordium_test]
+    fn test_burn_not_authorized() {
+        // Setup the context
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(0));
+        ctx.set_sender(ADDRESS_1);
+
+        // and parameter.
+        let parameter = BurnParams {
+            owner: ADDRESS_0,
+            token_id: token_0(),
+            amount: ContractTokenAmount::from(100),
+        };
+        let parameter_bytes = to_bytes(&parameter);
+        ctx.set_parameter(&parameter_bytes);
+
+        let mut logger = TestLogger::init();
+        let mut state_builder = TestStateBuilder::new();
+        let state = initial_state(&mut state_builder);
+        let mut host = TestHost::new(state, state_builder);
+
+        // Call the contract function.
+        let result: ContractResult<()> = contract_burn(&ctx, &mut host, &mut logger);
+
+        // Check the result.
+        let err = result.expect_err_report("Expected to fail");
+        claim_eq!(
+            err,
+            ContractError::Unauthorized,
+            "Error is expected to be Unauthorized"
+        )
+    }
+
+    /// Test an operator can burn the entire supply of a token type, after which
+    /// the token type is kept with a zero supply and cannot be minted anew.
+    #[concordium_test]
+    fn test_operator_burn_entire_supply() {
+        // Setup the context
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(0));
+        ctx.set_sender(ADDRESS_1);
+
+        // and parameter.
+        let parameter = BurnParams {
+            owner: ADDRESS_0,
+            token_id: token_1(),
+            amount: ContractTokenAmount::from(1),
+        };
+        let parameter_bytes = to_bytes(&parameter);
+        ctx.set_parameter(&parameter_bytes);
+
+        let mut logger = TestLogger::init();
+        let mut state_builder = TestStateBuilder::new();
+        let mut state = initial_state(&mut state_builder);
+        state.add_operator(&ADDRESS_0, &ADDRESS_1, None, &mut state_builder);
+        let mut host = TestHost::new(state, state_builder);
+
+        // Call the contract function.
+        let result: ContractResult<()> = contract_burn(&ctx, &mut host, &mut logger);
+
+        // Check the result.
+        claim!(result.is_ok(), "Results in rejection");
+
+        // Check the state.
+        claim_eq!(
+            host.state().total_supply(&token_1()),
+            Ok(0.into()),
+            "Token should be kept with a zero supply once the entire supply is burned"
+        );
+        claim_eq!(
+            host.state().total_supply(&token_0()),
+            Ok(400.into()),
+            "Other tokens should not be affected"
+        );
+
+        // The burned token ID cannot be minted anew.
+        let mut tokens = collections::BTreeMap::new();
+        tokens.insert(
+            token_1(),
+            TokenMintParams {
+                metadata: Some(TokenMetadata {
+                    url: "url".to_string(),
+                    hash: None,
+                }),
+                max_supply: 100.into(),
+                amount: 1.into(),
+                soulbound: false,
+                royalties: None,
+            },
+        );
+        let parameter_bytes = to_bytes(&MintParams {
+            owner: ADDRESS_0,
+            tokens,
+        });
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(ADDRESS_0);
+        ctx.set_parameter(&parameter_bytes);
+        let result: ContractResult<()> = contract_mint(&ctx, &mut host, &mut logger);
+        claim_eq!(
+            result,
+            Err(ContractError::Custom(
+                CustomContractError::TokenAlreadyMinted
+            )),
+            "Minting a burned token ID anew should fail"
+        );
+    }
+
+    /// Test the total supply is tracked through minting and burning and is
+    /// reported by the `totalSupply` query.
+    #[concordium_test]
+    fn test_total_supply() {
+        // Setup the context
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(ADDRESS_0);
+
+        let mut state_builder = TestStateBuilder::new();
+        let mut state = initial_state(&mut state_builder);
+        state
+            .burn(&token_0(), 150.into(), &ADDRESS_0)
+            .expect_report("Burning is expected to succeed");
+        let host = TestHost::new(state, state_builder);
+
+        // and parameter.
+        let parameter = TotalSupplyQueryParams {
+            queries: vec![token_0(), token_1()],
+        };
+        let parameter_bytes = to_bytes(&parameter);
+        ctx.set_parameter(&parameter_bytes);
+
+        // Call the contract function.
+        let result: ContractResult<TotalSupplyQueryResponse> = contract_total_supply(&ctx, &host);
+
+        // Check the result.
+        claim_eq!(
+            result.expect_report("Failed getting result value").0,
+            vec![ContractTokenAmount::from(250), ContractTokenAmount::from(1)],
+            "Total supply should account for minted and burned tokens"
+        );
+    }
+
+    /// Test minting more of an existing token type succeeds up to its maximum
+    /// supply.
+    #[concordium_test]
+    fn test_mint_more() {
+        // Setup the context
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(ADDRESS_0);
+        ctx.set_owner(ACCOUNT_0);
+
+        // and parameter.
+        let mut tokens = collections::BTreeMap::new();
+        tokens.insert(token_0(), ContractTokenAmount::from(600));
+        let parameter = MintMoreParams {
+            owner: ADDRESS_1,
+            tokens,
+        };
+        let parameter_bytes = to_bytes(&parameter);
+        ctx.set_parameter(&parameter_bytes);
+
+        let mut logger = TestLogger::init();
+        let mut state_builder = TestStateBuilder::new();
+        let state = initial_state(&mut state_builder);
+        let mut host = TestHost::new(state, state_builder);
+
+        // Call the contract function.
+        let result: ContractResult<()> = contract_mint_more(&ctx, &mut host, &mut logger);
+
+        // Check the result.
+        claim!(result.is_ok(), "Results in rejection");
+
+        // Check the state.
+        let balance1 = host
+            .state()
+            .balance(&token_0(), &ADDRESS_1)
+            .expect_report("Token is expected to exist");
+        claim_eq!(
+            balance1,
+            600.into(),
+            "Minted tokens should be owned by ADDRESS_1"
+        );
+        claim_eq!(
+            host.state().total_supply(&token_0()),
+            Ok(1000.into()),
+            "Total supply should reach the maximum supply"
+        );
+
+        // Check the logs.
+        claim_eq!(logger.logs.len(), 1, "Only one event should be logged");
+        claim_eq!(
+            logger.logs[0],
+            to_bytes(&Cis2Event::Mint(MintEvent {
+                owner: ADDRESS_1,
+                token_id: token_0(),
+                amount: ContractTokenAmount::from(600),
+            })),
+            "Incorrect event emitted"
+        );
+
+        // Minting beyond the maximum supply fails.
+        let mut tokens = collections::BTreeMap::new();
+        tokens.insert(token_0(), ContractTokenAmount::from(1));
+        let parameter = MintMoreParams {
+            owner: ADDRESS_1,
+            tokens,
+        };
+        let parameter_bytes = to_bytes(&parameter);
+        ctx.set_parameter(&parameter_bytes);
+
+        let result: ContractResult<()> = contract_mint_more(&ctx, &mut host, &mut logger);
+        claim_eq!(
+            result,
+            Err(ContractError::Custom(
+                CustomContractError::MaxSupplyExceeded
+            )),
+            "Minting above the maximum supply should fail"
+        );
+    }
+
+    /// Test minting fails, when the sender does not have the `Minter` role.
+    #[concordium_test]
+    fn test_mint_not_minter() {
+        // Setup the context
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(ADDRESS_1);
+
+        // and parameter.
+        let mut tokens = collections::BTreeMap::new();
+        tokens.insert(token_0(), ContractTokenAmount::from(1));
+        let parameter = MintMoreParams {
+            owner: ADDRESS_1,
+            tokens,
+        };
+        let parameter_bytes = to_bytes(&parameter);
+        ctx.set_parameter(&parameter_bytes);
+
+        let mut logger = TestLogger::init();
+        let mut state_builder = TestStateBuilder::new();
+        let state = initial_state(&mut state_builder);
+        let mut host = TestHost::new(state, state_builder);
+
+        // Call the contract function.
+        let result: ContractResult<()> = contract_mint_more(&ctx, &mut host, &mut logger);
+
+        // Check the result.
+        claim_eq!(
+            result,
+            Err(ContractError::Unauthorized),
+            "Error is expected to be Unauthorized"
+        );
+    }
+
+    /// Test an admin can grant a role, the appropriate event is logged and the
+    /// role is reported by the `hasRole` query.
+    #[concordium_test]
+    fn test_grant_role() {
+        // Setup the context
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(ADDRESS_0);
+
+        // and parameter.
+        let parameter = RoleParams {
+            address: ADDRESS_1,
+            role: Role::Minter,
+        };
+        let parameter_bytes = to_bytes(&parameter);
+        ctx.set_parameter(&parameter_bytes);
+
+        let mut logger = TestLogger::init();
+        let mut state_builder = TestStateBuilder::new();
+        let state = initial_state(&mut state_builder);
+        let mut host = TestHost::new(state, state_builder);
+
+        // Call the contract function.
+        let result: ContractResult<()> = contract_grant_role(&ctx, &mut host, &mut logger);
+
+        // Check the result.
+        claim!(result.is_ok(), "Results in rejection");
+
+        // Check the logs.
+        claim_eq!(logger.logs.len(), 1, "One event should be logged");
+        claim_eq!(
+            logger.logs[0],
+            to_bytes(&CustomEvent::RoleGranted(RoleEvent {
+                address: ADDRESS_1,
+                role: Role::Minter,
+            })),
+            "Incorrect event emitted"
+        );
+
+        // Check the `hasRole` query.
+        let parameter = HasRoleQueryParams {
+            queries: vec![
+                RoleParams {
+                    address: ADDRESS_1,
+                    role: Role::Minter,
                 },
+                RoleParams {
+                    address: ADDRESS_1,
+                    role: Role::Admin,
+                },
+            ],
+        };
+        let parameter_bytes = to_bytes(&parameter);
+        ctx.set_parameter(&parameter_bytes);
+
+        let result: ContractResult<HasRoleQueryResponse> = contract_has_role(&ctx, &host);
+        claim_eq!(
+            result.expect_report("Failed getting result value").0,
+            vec![true, false],
+            "Only the granted role should be reported"
+        );
+
+        // A non-admin cannot grant roles.
+        ctx.set_sender(ADDRESS_1);
+        let parameter = RoleParams {
+            address: ADDRESS_1,
+            role: Role::Admin,
+        };
+        let parameter_bytes = to_bytes(&parameter);
+        ctx.set_parameter(&parameter_bytes);
+
+        let result: ContractResult<()> = contract_grant_role(&ctx, &mut host, &mut logger);
+        claim_eq!(
+            result,
+            Err(ContractError::Unauthorized),
+            "Error is expected to be Unauthorized"
+        );
+
+        // The admin cannot grant the `Admin` role either.
+        ctx.set_sender(ADDRESS_0);
+        let result: ContractResult<()> = contract_grant_role(&ctx, &mut host, &mut logger);
+        claim_eq!(
+            result,
+            Err(ContractError::Custom(
+                CustomContractError::AdminRoleNotGrantable
+            )),
+            "Granting the Admin role should fail"
+        );
+    }
+
+    /// Test the token ID type selected by the cargo features is described by
+    /// the schema as a list of bytes and serializes prefixed by its length, as
+    /// required by the CIS2 specification.
+    #[concordium_test]
+    fn test_token_id_schema() {
+        claim_eq!(
+            <ContractTokenId as schema::SchemaType>::get_type(),
+            schema::Type::ByteList(schema::SizeLength::U8),
+            "Token ID should be described as a list of bytes"
+        );
+
+        let bytes = to_bytes(&token_1());
+        claim_eq!(
+            usize::from(bytes[0]),
+            bytes.len() - 1,
+            "Token ID should be prefixed by its length"
+        );
+        claim_eq!(
+            from_bytes::<ContractTokenId>(&bytes),
+            Ok(token_1()),
+            "Token ID should deserialize to the serialized token ID"
+        );
+    }
+
+    /// Test updating the metadata of a token type succeeds and logs a
+    /// `TokenMetadata` event, and that it fails once the metadata is frozen.
+    #[concordium_test]
+    fn test_update_and_freeze_token_metadata() {
+        // Setup the context
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(ADDRESS_0);
+
+        // and parameter.
+        let metadata = TokenMetadata {
+            url: "new_url".to_string(),
+            hash: None,
+        };
+        let parameter = UpdateTokenMetadataParams(vec![UpdateTokenMetadataParam {
+            token_id: token_0(),
+            metadata: metadata.clone(),
+        }]);
+        let update_parameter_bytes = to_bytes(&parameter);
+        ctx.set_parameter(&update_parameter_bytes);
+
+        let mut logger = TestLogger::init();
+        let mut state_builder = TestStateBuilder::new();
+        let state = initial_state(&mut state_builder);
+        let mut host = TestHost::new(state, state_builder);
+
+        // Call the contract function.
+        let result: ContractResult<()> =
+            contract_update_token_metadata(&ctx, &mut host, &mut logger);
+
+        // Check the result.
+        claim!(result.is_ok(), "Results in rejection");
+
+        // Check the state.
+        claim_eq!(
+            host.state()
+                .tokens
+                .get(&token_0())
+                .and_then(|token_state| token_state.metadata_url.clone())
+                .map(|metadata_url| metadata_url.url),
+            Some("new_url".to_string()),
+            "Metadata URL should be updated"
+        );
+
+        // Check the logs.
+        claim_eq!(logger.logs.len(), 1, "Only one event should be logged");
+        claim_eq!(
+            logger.logs[0],
+            to_bytes(&Cis2Event::TokenMetadata::<_, ContractTokenAmount>(
+                TokenMetadataEvent {
+                    token_id: token_0(),
+                    metadata_url: metadata.to_metadata_url(),
+                }
+            )),
+            "Incorrect event emitted"
+        );
+
+        // Freeze the metadata of the token type.
+        let parameter = FreezeMetadataParams::Tokens(vec![token_0()]);
+        let parameter_bytes = to_bytes(&parameter);
+        ctx.set_parameter(&parameter_bytes);
+
+        let result: ContractResult<()> = contract_freeze_metadata(&ctx, &mut host, &mut logger);
+        claim!(result.is_ok(), "Results in rejection");
+        claim_eq!(
+            logger.logs[1],
+            to_bytes(&CustomEvent::MetadataFrozen(MetadataFrozenEvent {
+                token_id: Some(token_0()),
+            })),
+            "Incorrect event emitted"
+        );
+
+        // Updating the frozen metadata fails.
+        ctx.set_parameter(&update_parameter_bytes);
+        let result: ContractResult<()> =
+            contract_update_token_metadata(&ctx, &mut host, &mut logger);
+        claim_eq!(
+            result,
+            Err(ContractError::Custom(CustomContractError::MetadataFrozen)),
+            "Updating frozen metadata should fail"
+        );
+    }
+
+    /// Test updating the metadata of any token type fails once the metadata of
+    /// the collection is frozen.
+    #[concordium_test]
+    fn test_freeze_collection_metadata() {
+        // Setup the context
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(ADDRESS_0);
+
+        // and parameter.
+        let parameter_bytes = to_bytes(&FreezeMetadataParams::Collection);
+        ctx.set_parameter(&parameter_bytes);
+
+        let mut logger = TestLogger::init();
+        let mut state_builder = TestStateBuilder::new();
+        let state = initial_state(&mut state_builder);
+        let mut host = TestHost::new(state, state_builder);
+
+        // Call the contract function.
+        let result: ContractResult<()> = contract_freeze_metadata(&ctx, &mut host, &mut logger);
+
+        // Check the result.
+        claim!(result.is_ok(), "Results in rejection");
+
+        // Check the logs.
+        claim_eq!(
+            logger.logs,
+            vec![to_bytes(&CustomEvent::MetadataFrozen(
+                MetadataFrozenEvent { token_id: None }
+            ))],
+            "Incorrect event emitted"
+        );
+
+        // Check the state.
+        let metadata = TokenMetadata {
+            url: "new_url".to_string(),
+            hash: None,
+        };
+        claim_eq!(
+            host.state_mut().update_metadata(&token_1(), &metadata),
+            Err(ContractError::Custom(CustomContractError::MetadataFrozen)),
+            "Updating frozen metadata should fail"
+        );
+    }
+
+    /// Test transfers are rejected while the contract is paused and succeed
+    /// again once it is unpaused, while queries keep working.
+    #[concordium_test]
+    fn test_pause() {
+        // Setup the context
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(0));
+        ctx.set_sender(ADDRESS_0);
+
+        let mut logger = TestLogger::init();
+        let mut state_builder = TestStateBuilder::new();
+        let mut state = initial_state(&mut state_builder);
+        state.grant_role(&ADDRESS_0, Role::Pauser);
+        let mut host = TestHost::new(state, state_builder);
+
+        // Pause the contract.
+        let result: ContractResult<()> = contract_pause(&ctx, &mut host);
+        claim!(result.is_ok(), "Results in rejection");
+        claim_eq!(
+            contract_is_paused(&ctx, &host),
+            Ok(true),
+            "Contract should be paused"
+        );
+
+        // Transfers are rejected.
+        let transfer = Transfer {
+            token_id: token_0(),
+            amount: ContractTokenAmount::from(100),
+            from: ADDRESS_0,
+            to: Receiver::from_account(ACCOUNT_1),
+            data: AdditionalData::empty(),
+        };
+        let parameter = TransferParams::from(vec![transfer]);
+        let transfer_parameter_bytes = to_bytes(&parameter);
+        ctx.set_parameter(&transfer_parameter_bytes);
+
+        let result: ContractResult<()> = contract_transfer(&ctx, &mut host, &mut logger);
+        claim_eq!(
+            result,
+            Err(ContractError::Custom(CustomContractError::Paused)),
+            "Transfer should fail while paused"
+        );
+
+        // Queries keep working.
+        let parameter = BalanceOfQueryParams {
+            queries: vec![BalanceOfQuery {
+                token_id: token_0(),
+                address: ADDRESS_0,
+            }],
+        };
+        let parameter_bytes = to_bytes(&parameter);
+        ctx.set_parameter(&parameter_bytes);
+        let result: ContractResult<ContractBalanceOfQueryResponse> =
+            contract_balance_of(&ctx, &host);
+        claim_eq!(
+            result.expect_report("Failed getting result value").0,
+            vec![ContractTokenAmount::from(400)],
+            "Balance should be reported while paused"
+        );
+
+        // Unpause the contract and transfer.
+        let result: ContractResult<()> = contract_unpause(&ctx, &mut host);
+        claim!(result.is_ok(), "Results in rejection");
+
+        ctx.set_parameter(&transfer_parameter_bytes);
+        let result: ContractResult<()> = contract_transfer(&ctx, &mut host, &mut logger);
+        claim!(result.is_ok(), "Transfer should succeed once unpaused");
+    }
+
+    /// Test pausing fails, when the sender does not have the `Pauser` role.
+    #[concordium_test]
+    fn test_pause_not_pauser() {
+        // Setup the context
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(ADDRESS_1);
+
+        let mut state_builder = TestStateBuilder::new();
+        let state = initial_state(&mut state_builder);
+        let mut host = TestHost::new(state, state_builder);
+
+        // Call the contract function.
+        let result: ContractResult<()> = contract_pause(&ctx, &mut host);
+
+        // Check the result.
+        claim_eq!(
+            result,
+            Err(ContractError::Unauthorized),
+            "Error is expected to be Unauthorized"
+        );
+        claim!(!host.state().paused, "Contract should not be paused");
+    }
+
+    /// Test minting fails, when the hash of the token metadata is invalid.
+    #[concordium_test]
+    fn test_mint_invalid_metadata_hash() {
+        // Setup the context
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(ADDRESS_0);
+
+        // and parameter.
+        let mut tokens = collections::BTreeMap::new();
+        tokens.insert(
+            token_0(),
+            TokenMintParams {
+                metadata: Some(TokenMetadata {
+                    url: "url".to_string(),
+                    hash: Some([0u8; 32]),
+                }),
+                max_supply: 1.into(),
+                amount: 1.into(),
+                soulbound: false,
+                royalties: None,
+            },
+        );
+        let parameter = MintParams {
+            owner: ADDRESS_0,
+            tokens,
+        };
+        let parameter_bytes = to_bytes(&parameter);
+        ctx.set_parameter(&parameter_bytes);
+
+        let mut logger = TestLogger::init();
+        let mut state_builder = TestStateBuilder::new();
+        let mut state = State::empty(
+            &mut state_builder,
+            CollectionInfo::default(),
+            ADDRESS_0,
+            ACCOUNT_0,
+            None,
+        );
+        state.grant_role(&ADDRESS_0, Role::Minter);
+        let mut host = TestHost::new(state, state_builder);
+
+        // Call the contract function.
+        let result: ContractResult<()> = contract_mint(&ctx, &mut host, &mut logger);
+
+        // Check the result.
+        claim_eq!(
+            result,
+            Err(ContractError::Custom(
+                CustomContractError::InvalidMetadataHash
+            )),
+            "Error is expected to be InvalidMetadataHash"
+        );
+        claim!(
+            !host.state().contains_token(&token_0()),
+            "Token should not be minted"
+        );
+    }
+
+    /// Test a page is capped at `MAX_PAGE_LIMIT` items, whatever the limit
+    /// requested, and the next page follows the cursor.
+    #[concordium_test]
+    fn test_paginate_limit() {
+        let items = || (0u32..150).map(|i| (i, ()));
+
+        let (page, cursor) = paginate(items(), None, u32::MAX);
+        claim_eq!(page.len(), MAX_PAGE_LIMIT as usize, "Page should be capped");
+        claim_eq!(
+            cursor,
+            Some(99),
+            "Cursor should be the last item of the page"
+        );
+
+        let (page, cursor) = paginate(items(), cursor, u32::MAX);
+        claim_eq!(
+            page.first(),
+            Some(&(100, ())),
+            "Page should follow the cursor"
+        );
+        claim_eq!(page.len(), 50, "Page should contain the remaining items");
+        claim_eq!(cursor, None, "There should be no more pages");
+    }
+
+    /// Test the tokens are reported one page at a time by `viewTokens`.
+    #[concordium_test]
+    fn test_view_tokens_pagination() {
+        // Setup the context
+        let mut ctx = TestReceiveContext::empty();
+
+        let mut state_builder = TestStateBuilder::new();
+        let state = initial_state(&mut state_builder);
+        let host = TestHost::new(state, state_builder);
+
+        // Query the first page.
+        let parameter = ViewTokensParams {
+            cursor: None,
+            limit: 1,
+        };
+        let parameter_bytes = to_bytes(&parameter);
+        ctx.set_parameter(&parameter_bytes);
+
+        let result: ContractResult<ViewTokensResponse> = contract_view_tokens(&ctx, &host);
+        let page = result.expect_report("Failed getting result value");
+        claim_eq!(
+            page.tokens,
+            vec![(token_0(), ContractTokenAmount::from(400))],
+            "First page should contain the first token"
+        );
+        claim_eq!(
+            page.cursor,
+            Some(token_0()),
+            "Cursor should point to the last token of the page"
+        );
+
+        // Query the next page.
+        let parameter = ViewTokensParams {
+            cursor: page.cursor,
+            limit: 1,
+        };
+        let parameter_bytes = to_bytes(&parameter);
+        ctx.set_parameter(&parameter_bytes);
+
+        let result: ContractResult<ViewTokensResponse> = contract_view_tokens(&ctx, &host);
+        let page = result.expect_report("Failed getting result value");
+        claim_eq!(
+            page.tokens,
+            vec![(token_1(), ContractTokenAmount::from(1))],
+            "Second page should contain the second token"
+        );
+        claim_eq!(page.cursor, None, "There should be no more pages");
+    }
+
+    /// Test the holders of a token and the balances of an address are reported
+    /// by `holdersOf` and `tokensOf`.
+    #[concordium_test]
+    fn test_holders_of_and_tokens_of() {
+        // Setup the context
+        let mut ctx = TestReceiveContext::empty();
+
+        let mut state_builder = TestStateBuilder::new();
+        let mut state = initial_state(&mut state_builder);
+        state
+            .transfer(
+                &token_0(),
+                100.into(),
+                &ADDRESS_0,
+                &ADDRESS_1,
+                &mut state_builder,
+            )
+            .expect_report("Transfer is expected to succeed");
+        let host = TestHost::new(state, state_builder);
+
+        // Query the holders.
+        let parameter = HoldersOfParams {
+            token_id: token_0(),
+            cursor: None,
+            limit: 10,
+        };
+        let parameter_bytes = to_bytes(&parameter);
+        ctx.set_parameter(&parameter_bytes);
+
+        let result: ContractResult<HoldersOfResponse> = contract_holders_of(&ctx, &host);
+        let page = result.expect_report("Failed getting result value");
+        claim_eq!(
+            page.holders,
+            vec![
+                (ADDRESS_0, ContractTokenAmount::from(300)),
+                (ADDRESS_1, ContractTokenAmount::from(100))
+            ],
+            "Both addresses should hold the token"
+        );
+        claim_eq!(page.cursor, None, "There should be no more pages");
+
+        // Query the balances.
+        let parameter = TokensOfParams {
+            address: ADDRESS_0,
+            cursor: None,
+            limit: 10,
+        };
+        let parameter_bytes = to_bytes(&parameter);
+        ctx.set_parameter(&parameter_bytes);
+
+        let result: ContractResult<TokensOfResponse> = contract_tokens_of(&ctx, &host);
+        let page = result.expect_report("Failed getting result value");
+        claim_eq!(
+            page.tokens,
+            vec![
+                (token_0(), ContractTokenAmount::from(300)),
+                (token_1(), ContractTokenAmount::from(1))
+            ],
+            "Address should hold both tokens"
+        );
+    }
+
+    /// Test a balance transferred away entirely is pruned and no longer listed
+    /// by `tokensOf`, while the receiver lists it one page at a time.
+    #[concordium_test]
+    fn test_tokens_of_after_transfer() {
+        // Setup the context
+        let mut ctx = TestReceiveContext::empty();
+
+        let mut state_builder = TestStateBuilder::new();
+        let mut state = initial_state(&mut state_builder);
+        state
+            .transfer(
+                &token_1(),
+                1.into(),
+                &ADDRESS_0,
+                &ADDRESS_1,
+                &mut state_builder,
+            )
+            .expect_report("Transfer is expected to succeed");
+        state
+            .transfer(
+                &token_0(),
                 400.into(),
-            ),
+                &ADDRESS_0,
+                &ADDRESS_1,
+                &mut state_builder,
+            )
+            .expect_report("Transfer is expected to succeed");
+        let host = TestHost::new(state, state_builder);
+
+        // Check the balances of the sender are pruned.
+        let address_state = host
+            .state()
+            .state
+            .get(&ADDRESS_0)
+            .expect_report("Address state should remain");
+        claim!(
+            address_state.balances.iter().next().is_none(),
+            "Zero balances should be removed"
+        );
+
+        // Query the sender.
+        let parameter = TokensOfParams {
+            address: ADDRESS_0,
+            cursor: None,
+            limit: 10,
+        };
+        let parameter_bytes = to_bytes(&parameter);
+        ctx.set_parameter(&parameter_bytes);
+
+        let result: ContractResult<TokensOfResponse> = contract_tokens_of(&ctx, &host);
+        let page = result.expect_report("Failed getting result value");
+        claim_eq!(page.tokens, Vec::new(), "Sender should hold no tokens");
+        claim_eq!(page.cursor, None, "There should be no more pages");
+
+        // Query the receiver one token at a time.
+        let parameter = TokensOfParams {
+            address: ADDRESS_1,
+            cursor: None,
+            limit: 1,
+        };
+        let parameter_bytes = to_bytes(&parameter);
+        ctx.set_parameter(&parameter_bytes);
+
+        let result: ContractResult<TokensOfResponse> = contract_tokens_of(&ctx, &host);
+        let page = result.expect_report("Failed getting result value");
+        claim_eq!(
+            page.tokens,
+            vec![(token_0(), ContractTokenAmount::from(400))],
+            "First page should contain the first token"
+        );
+
+        let parameter = TokensOfParams {
+            address: ADDRESS_1,
+            cursor: page.cursor,
+            limit: 1,
+        };
+        let parameter_bytes = to_bytes(&parameter);
+        ctx.set_parameter(&parameter_bytes);
+
+        let result: ContractResult<TokensOfResponse> = contract_tokens_of(&ctx, &host);
+        let page = result.expect_report("Failed getting result value");
+        claim_eq!(
+            page.tokens,
+            vec![(token_1(), ContractTokenAmount::from(1))],
+            "Second page should contain the second token"
+        );
+        claim_eq!(page.cursor, None, "There should be no more pages");
+    }
+
+    /// Test the holder index stays consistent with the balances of each
+    /// address through minting, transferring and burning.
+    #[concordium_test]
+    fn test_holders_index_consistency() {
+        let mut state_builder = TestStateBuilder::new();
+        let mut state = initial_state(&mut state_builder);
+
+        // Check every address listed for a token holds it and every address
+        // holding a token is listed.
+        let check_consistency = |state: &State<TestStateApi>| {
+            for (token_id, _) in state.tokens.iter() {
+                let holders: Vec<Address> = state
+                    .holders
+                    .get(&token_id)
+                    .map(|holders| holders.iter().map(|address| *address).collect())
+                    .unwrap_or_default();
+                for (address, address_state) in state.state.iter() {
+                    let holds = match address_state.balances.get(&token_id) {
+                        Some(balance) => *balance > 0.into(),
+                        None => false,
+                    };
+                    claim_eq!(
+                        holders.contains(&address),
+                        holds,
+                        "Holder index should match the balances"
+                    );
+                }
+            }
+        };
+        check_consistency(&state);
+
+        state
+            .transfer(
+                &token_0(),
+                100.into(),
+                &ADDRESS_0,
+                &ADDRESS_1,
+                &mut state_builder,
+            )
+            .expect_report("Transfer is expected to succeed");
+        check_consistency(&state);
+
+        state
+            .transfer(
+                &token_1(),
+                1.into(),
+                &ADDRESS_0,
+                &ADDRESS_1,
+                &mut state_builder,
+            )
+            .expect_report("Transfer is expected to succeed");
+        check_consistency(&state);
+        claim!(
+            !state
+                .holders
+                .get(&token_1())
+                .expect_report("Token should be indexed")
+                .contains(&ADDRESS_0),
+            "Sender should no longer be a holder"
+        );
+
+        state
+            .burn(&token_0(), 100.into(), &ADDRESS_1)
+            .expect_report("Burn is expected to succeed");
+        check_consistency(&state);
+
+        state
+            .burn(&token_1(), 1.into(), &ADDRESS_1)
+            .expect_report("Burn is expected to succeed");
+        check_consistency(&state);
+        claim!(
+            state.holders.get(&token_1()).is_none(),
+            "Burned token should not be indexed"
+        );
+    }
+
+    /// The drop of `token_0` used in the tests, open from time 1000 until time
+    /// 2000.
+    fn drop_config() -> DropConfig {
+        DropConfig {
+            price: Amount::from_micro_ccd(100),
+            max_per_wallet: 3.into(),
+            max_minted: 5.into(),
+            start: Timestamp::from_timestamp_millis(1000),
+            end: Timestamp::from_timestamp_millis(2000),
+        }
+    }
+
+    /// Test buying tokens in a drop mints them to the buyer, forwards the
+    /// payment to the treasury and respects the wallet limit.
+    #[concordium_test]
+    fn test_public_mint() {
+        // Setup the context
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(ADDRESS_0);
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(1500));
+
+        let mut logger = TestLogger::init();
+        let mut state_builder = TestStateBuilder::new();
+        let state = initial_state(&mut state_builder);
+        let mut host = TestHost::new(state, state_builder);
+
+        // Configure the drop.
+        let parameter = SetDropParams {
+            token_id: token_0(),
+            config: drop_config(),
+        };
+        let parameter_bytes = to_bytes(&parameter);
+        ctx.set_parameter(&parameter_bytes);
+        let result: ContractResult<()> = contract_set_drop(&ctx, &mut host);
+        claim!(result.is_ok(), "Results in rejection");
+
+        // Buy tokens.
+        ctx.set_sender(ADDRESS_1);
+        let parameter = PublicMintParams {
+            token_id: token_0(),
+            amount: 2.into(),
+        };
+        let parameter_bytes = to_bytes(&parameter);
+        ctx.set_parameter(&parameter_bytes);
+        host.set_self_balance(Amount::from_micro_ccd(200));
+
+        let result: ContractResult<()> =
+            contract_public_mint(&ctx, &mut host, Amount::from_micro_ccd(200), &mut logger);
+        claim!(result.is_ok(), "Results in rejection");
+
+        // Check the state.
+        claim_eq!(
+            host.state().balance(&token_0(), &ADDRESS_1),
+            Ok(2.into()),
+            "Buyer should own the minted tokens"
+        );
+        claim_eq!(
+            host.get_transfers(),
+            [(ACCOUNT_0, Amount::from_micro_ccd(200))],
+            "Payment should be forwarded to the treasury"
+        );
+        claim!(
+            logger.logs.contains(&to_bytes(&Cis2Event::Mint(MintEvent {
+                owner: ADDRESS_1,
+                token_id: token_0(),
+                amount: ContractTokenAmount::from(2),
+            }))),
+            "Expected an event for minting token_0"
+        );
+
+        // Buying more than the wallet limit is rejected.
+        let result: ContractResult<()> =
+            contract_public_mint(&ctx, &mut host, Amount::from_micro_ccd(200), &mut logger);
+        claim_eq!(
+            result,
+            Err(ContractError::Custom(
+                CustomContractError::WalletLimitExceeded
+            )),
+            "Minting above the wallet limit should fail"
+        );
+
+        // Paying the wrong price is rejected.
+        let parameter = PublicMintParams {
+            token_id: token_0(),
+            amount: 1.into(),
+        };
+        let parameter_bytes = to_bytes(&parameter);
+        ctx.set_parameter(&parameter_bytes);
+        let result: ContractResult<()> =
+            contract_public_mint(&ctx, &mut host, Amount::from_micro_ccd(50), &mut logger);
+        claim_eq!(
+            result,
+            Err(ContractError::Custom(CustomContractError::IncorrectPayment)),
+            "Minting with the wrong payment should fail"
+        );
+    }
+
+    /// Test buying tokens outside the time window of the drop is rejected.
+    #[concordium_test]
+    fn test_public_mint_not_active() {
+        // Setup the context
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(ADDRESS_1);
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(2000));
+
+        let mut logger = TestLogger::init();
+        let mut state_builder = TestStateBuilder::new();
+        let mut state = initial_state(&mut state_builder);
+        state.set_drop(&token_0(), drop_config(), &mut state_builder);
+        let mut host = TestHost::new(state, state_builder);
+
+        let parameter = PublicMintParams {
+            token_id: token_0(),
+            amount: 1.into(),
+        };
+        let parameter_bytes = to_bytes(&parameter);
+        ctx.set_parameter(&parameter_bytes);
+
+        let result: ContractResult<()> =
+            contract_public_mint(&ctx, &mut host, Amount::from_micro_ccd(100), &mut logger);
+        claim_eq!(
+            result,
+            Err(ContractError::Custom(CustomContractError::DropNotActive)),
+            "Minting after the drop closed should fail"
+        );
+    }
+
+    /// Test allowlisted accounts can mint up to their allowance using a proof
+    /// built with the `merkle` module, while other entries are rejected.
+    #[concordium_test]
+    #[cfg(feature = "crypto-primitives")]
+    fn test_allowlist_mint() {
+        let crypto_primitives = TestCryptoPrimitives::new();
+        // Build the allowlist.
+        let allowlist = [
+            (ACCOUNT_0, ContractTokenAmount::from(1)),
+            (ACCOUNT_1, ContractTokenAmount::from(2)),
+            (AccountAddress([2u8; 32]), ContractTokenAmount::from(3)),
+        ];
+        let leaves = allowlist
+            .iter()
+            .map(|(account, allowance)| merkle::hash_leaf(&crypto_primitives, account, *allowance))
+            .collect();
+        let tree = merkle::MerkleTree::new(&crypto_primitives, leaves);
+
+        // Setup the context
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(ADDRESS_0);
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(1500));
+
+        let mut logger = TestLogger::init();
+        let mut state_builder = TestStateBuilder::new();
+        let mut state = initial_state(&mut state_builder);
+        state.set_drop(&token_0(), drop_config(), &mut state_builder);
+        let mut host = TestHost::new(state, state_builder);
+
+        // Set the allowlist.
+        let parameter = SetAllowlistParams {
+            token_id: token_0(),
+            root: Some(tree.root()),
+        };
+        let parameter_bytes = to_bytes(&parameter);
+        ctx.set_parameter(&parameter_bytes);
+        let result: ContractResult<()> = contract_set_allowlist(&ctx, &mut host);
+        claim!(result.is_ok(), "Results in rejection");
+
+        // Mint the entire allowance.
+        ctx.set_sender(ADDRESS_1);
+        let parameter = AllowlistMintParams {
+            token_id: token_0(),
+            amount: 2.into(),
+            allowance: 2.into(),
+            proof: tree.proof(1),
+        };
+        let parameter_bytes = to_bytes(&parameter);
+        ctx.set_parameter(&parameter_bytes);
+        host.set_self_balance(Amount::from_micro_ccd(200));
+
+        let result: ContractResult<()> = contract_allowlist_mint(
+            &ctx,
+            &mut host,
+            Amount::from_micro_ccd(200),
+            &mut logger,
+            &crypto_primitives,
+        );
+        claim!(result.is_ok(), "Results in rejection");
+        claim_eq!(
+            host.state().balance(&token_0(), &ADDRESS_1),
+            Ok(2.into()),
+            "Allowlisted account should own the minted tokens"
+        );
+
+        // Minting above the allowance is rejected.
+        let parameter = AllowlistMintParams {
+            token_id: token_0(),
+            amount: 1.into(),
+            allowance: 2.into(),
+            proof: tree.proof(1),
+        };
+        let parameter_bytes = to_bytes(&parameter);
+        ctx.set_parameter(&parameter_bytes);
+        let result: ContractResult<()> = contract_allowlist_mint(
+            &ctx,
+            &mut host,
+            Amount::from_micro_ccd(100),
+            &mut logger,
+            &crypto_primitives,
+        );
+        claim_eq!(
+            result,
+            Err(ContractError::Custom(
+                CustomContractError::AllowanceExceeded
+            )),
+            "Minting above the allowance should fail"
+        );
+
+        // Claiming a larger allowance invalidates the proof.
+        let parameter = AllowlistMintParams {
+            token_id: token_0(),
+            amount: 1.into(),
+            allowance: 3.into(),
+            proof: tree.proof(1),
+        };
+        let parameter_bytes = to_bytes(&parameter);
+        ctx.set_parameter(&parameter_bytes);
+        let result: ContractResult<()> = contract_allowlist_mint(
+            &ctx,
+            &mut host,
+            Amount::from_micro_ccd(100),
+            &mut logger,
+            &crypto_primitives,
+        );
+        claim_eq!(
+            result,
+            Err(ContractError::Custom(CustomContractError::InvalidProof)),
+            "Minting with an invalid proof should fail"
+        );
+    }
+
+    /// Test a voucher signed by a minter is redeemed by the buyer, creating
+    /// the token type, and cannot be redeemed again.
+    #[cfg(feature = "crypto-primitives")]
+    #[concordium_test]
+    fn test_redeem_voucher() {
+        use ed25519_dalek::{Keypair, PublicKey, SecretKey, Signer};
+
+        let secret = SecretKey::from_bytes(&[7u8; 32]).expect_report("Valid secret key");
+        let public = PublicKey::from(&secret);
+        let keypair = Keypair { secret, public };
+        let crypto_primitives = TestCryptoPrimitives::new();
+
+        let mut logger = TestLogger::init();
+        let mut state_builder = TestStateBuilder::new();
+        let state = initial_state(&mut state_builder);
+        let mut host = TestHost::new(state, state_builder);
+
+        // The minter registers its public key.
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(ADDRESS_0);
+        let parameter_bytes = to_bytes(&PublicKeyEd25519(public.to_bytes()));
+        ctx.set_parameter(&parameter_bytes);
+        let result: ContractResult<()> = contract_register_public_key(&ctx, &mut host);
+        claim!(result.is_ok(), "Results in rejection");
+
+        // Sign a voucher for a new token type.
+        let self_address = ContractAddress {
+            index: 0,
+            subindex: 0,
+        };
+        let voucher = MintVoucher {
+            contract_address: self_address,
+            token_id: new_token_id(7),
+            metadata: TokenMetadata {
+                url: "url".to_owned(),
+                hash: Some(METADATA_HASH),
+            },
+            max_supply: 10.into(),
+            amount: 3.into(),
+            price: Amount::from_micro_ccd(500),
+            expiry: Timestamp::from_timestamp_millis(10_000),
+            nonce: 0,
+        };
+        let voucher_hash = crypto_primitives
+            .hash_sha2_256(&voucher_message(&self_address, &voucher))
+            .0;
+        let signature = SignatureEd25519(keypair.sign(&voucher_hash).to_bytes());
+        let parameter = RedeemVoucherParams {
+            signature,
+            signer: ACCOUNT_0,
+            voucher,
+        };
+        let parameter_bytes = to_bytes(&parameter);
+
+        // A contract cannot redeem the voucher.
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(Address::Contract(ContractAddress {
+            index: 1,
+            subindex: 0,
+        }));
+        ctx.set_self_address(self_address);
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(1_000));
+        ctx.set_parameter(&parameter_bytes);
+        let result: ContractResult<()> = contract_redeem_voucher(
+            &ctx,
+            &mut host,
+            Amount::from_micro_ccd(500),
+            &mut logger,
+            &crypto_primitives,
+        );
+        claim_eq!(
+            result,
+            Err(ContractError::Custom(CustomContractError::AccountOnly)),
+            "Redeeming a voucher from a contract should fail"
+        );
+
+        // The buyer redeems the voucher.
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(ADDRESS_1);
+        ctx.set_self_address(self_address);
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(1_000));
+        ctx.set_parameter(&parameter_bytes);
+        host.set_self_balance(Amount::from_micro_ccd(500));
+
+        let result: ContractResult<()> = contract_redeem_voucher(
+            &ctx,
+            &mut host,
+            Amount::from_micro_ccd(500),
+            &mut logger,
+            &crypto_primitives,
+        );
+        claim!(result.is_ok(), "Results in rejection");
+
+        // Check the state.
+        claim_eq!(
+            host.state().balance(&new_token_id(7), &ADDRESS_1),
+            Ok(3.into()),
+            "Buyer should own the minted tokens"
+        );
+        claim_eq!(
+            host.get_transfers(),
+            [(ACCOUNT_0, Amount::from_micro_ccd(500))],
+            "Payment should be forwarded to the treasury"
+        );
+        claim_eq!(logger.logs.len(), 2, "Two events should be logged");
+
+        // Redeeming the voucher again is rejected.
+        host.set_self_balance(Amount::from_micro_ccd(500));
+        let result: ContractResult<()> = contract_redeem_voucher(
+            &ctx,
+            &mut host,
+            Amount::from_micro_ccd(500),
+            &mut logger,
+            &crypto_primitives,
+        );
+        claim_eq!(
+            result,
+            Err(ContractError::Custom(
+                CustomContractError::VoucherAlreadyRedeemed
+            )),
+            "Redeeming a voucher twice should fail"
+        );
+
+        // A signature over the voucher without the domain separator is
+        // rejected.
+        let voucher = MintVoucher {
+            contract_address: self_address,
+            token_id: new_token_id(7),
+            metadata: TokenMetadata {
+                url: "url".to_owned(),
+                hash: Some(METADATA_HASH),
+            },
+            max_supply: 10.into(),
+            amount: 3.into(),
+            price: Amount::from_micro_ccd(500),
+            expiry: Timestamp::from_timestamp_millis(10_000),
+            nonce: 1,
+        };
+        let voucher_hash = crypto_primitives.hash_sha2_256(&to_bytes(&voucher)).0;
+        let parameter_bytes = to_bytes(&RedeemVoucherParams {
+            signature: SignatureEd25519(keypair.sign(&voucher_hash).to_bytes()),
+            signer: ACCOUNT_0,
+            voucher,
+        });
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(ADDRESS_1);
+        ctx.set_self_address(self_address);
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(1_000));
+        ctx.set_parameter(&parameter_bytes);
+        let result: ContractResult<()> = contract_redeem_voucher(
+            &ctx,
+            &mut host,
+            Amount::from_micro_ccd(500),
+            &mut logger,
+            &crypto_primitives,
+        );
+        claim_eq!(
+            result,
+            Err(ContractError::Custom(CustomContractError::WrongSignature)),
+            "A voucher signed without the domain separator should fail"
         );
-        tokens.insert(
-            TOKEN_1,
-            (
-                {
-                    let url = "url".to_owned();
-                    let hash = "db2ca420a0090593ac6559ff2a98ce30abfe665d7a18ff3c63883e8b98622a73"
-                        .to_owned();
-                    TokenMetadata { url, hash }
-                },
-                1.into(),
-            ),
+    }
+
+    /// Test metadata URLs are derived from the base URL with `{id}`
+    /// substitution, unless the token type has an explicit metadata URL, and
+    /// frozen token types keep their URL when the base URL changes.
+    #[concordium_test]
+    fn test_base_url() {
+        let mut state_builder = TestStateBuilder::new();
+        let mut state = initial_state(&mut state_builder);
+        state.create_token(&new_token_id(7), None, 10.into(), false, None);
+        state.create_token(&new_token_id(8), None, 10.into(), false, None);
+        let token_id_hex = hex::encode(&to_bytes(&new_token_id(7))[1..]);
+
+        // Without a base URL there is no metadata URL.
+        claim_eq!(
+            state.metadata_url(&new_token_id(7)),
+            Err(ContractError::Custom(
+                CustomContractError::MissingMetadataUrl
+            )),
+            "Metadata URL should be missing"
         );
-        let parameter = MintParams {
-            owner: ADDRESS_0,
-            tokens,
+
+        // Derive the metadata URL using `{id}` substitution.
+        state
+            .set_base_url("https://example.com/{id}.json".to_string())
+            .expect_report("Setting the base URL is expected to succeed");
+        claim_eq!(
+            state
+                .metadata_url(&new_token_id(7))
+                .map(|metadata_url| metadata_url.url),
+            Ok(format!("https://example.com/{}.json", token_id_hex)),
+            "Metadata URL should substitute the token ID"
+        );
+        claim_eq!(
+            state
+                .metadata_url(&token_0())
+                .map(|metadata_url| metadata_url.url),
+            Ok("url".to_string()),
+            "Explicit metadata URL should take precedence"
+        );
+
+        // Freezing a token type keeps its metadata URL.
+        state
+            .freeze_token_metadata(&new_token_id(7))
+            .expect_report("Freezing is expected to succeed");
+        state
+            .set_base_url("https://example.org/".to_string())
+            .expect_report("Setting the base URL is expected to succeed");
+        claim_eq!(
+            state
+                .metadata_url(&new_token_id(7))
+                .map(|metadata_url| metadata_url.url),
+            Ok(format!("https://example.com/{}.json", token_id_hex)),
+            "Frozen metadata URL should not change"
+        );
+        claim_eq!(
+            state
+                .metadata_url(&new_token_id(8))
+                .map(|metadata_url| metadata_url.url),
+            Ok(format!(
+                "https://example.org/{}",
+                hex::encode(&to_bytes(&new_token_id(8))[1..])
+            )),
+            "Token ID should be appended to the base URL"
+        );
+    }
+
+    /// Test derived metadata URLs resolve to the placeholder until the reveal,
+    /// which must match the commitment and can only happen once.
+    #[concordium_test]
+    #[cfg(feature = "crypto-primitives")]
+    fn test_reveal() {
+        let crypto_primitives = TestCryptoPrimitives::new();
+
+        // Setup the context
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(ADDRESS_0);
+
+        let mut logger = TestLogger::init();
+        let mut state_builder = TestStateBuilder::new();
+        let mut state = initial_state(&mut state_builder);
+        state.create_token(&new_token_id(7), None, 10.into(), false, None);
+        state
+            .set_base_url("https://example.com/hidden/".to_string())
+            .expect_report("Setting the base URL is expected to succeed");
+        let mut host = TestHost::new(state, state_builder);
+
+        // Commit to the reveal.
+        let reveal = RevealParams {
+            base_url: "https://example.com/final/{id}.json".to_string(),
+            provenance_hash: [3u8; 32],
+        };
+        let placeholder = MetadataUrl {
+            url: "https://example.com/placeholder.json".to_string(),
+            hash: None,
+        };
+        let parameter = CommitRevealParams {
+            placeholder: placeholder.clone(),
+            commitment: crypto_primitives.hash_sha2_256(&to_bytes(&reveal)).0,
+        };
+        let parameter_bytes = to_bytes(&parameter);
+        ctx.set_parameter(&parameter_bytes);
+        let result: ContractResult<()> = contract_commit_reveal(&ctx, &mut host);
+        claim!(result.is_ok(), "Results in rejection");
+        claim_eq!(
+            host.state()
+                .metadata_url(&new_token_id(7))
+                .map(|metadata_url| metadata_url.url),
+            Ok(placeholder.url),
+            "Derived metadata URL should be the placeholder"
+        );
+
+        // The base URL cannot be replaced while the reveal is pending.
+        let parameter_bytes = to_bytes(&"https://example.com/other/".to_string());
+        ctx.set_parameter(&parameter_bytes);
+        let result: ContractResult<()> = contract_set_base_url(&ctx, &mut host);
+        claim_eq!(
+            result,
+            Err(ContractError::Custom(
+                CustomContractError::RevealAlreadyCommitted
+            )),
+            "Setting the base URL before the reveal should fail"
+        );
+
+        // A reveal not matching the commitment is rejected.
+        let parameter = RevealParams {
+            base_url: "https://example.com/other/".to_string(),
+            provenance_hash: [3u8; 32],
         };
         let parameter_bytes = to_bytes(&parameter);
         ctx.set_parameter(&parameter_bytes);
+        let result: ContractResult<()> =
+            contract_reveal(&ctx, &mut host, &mut logger, &crypto_primitives);
+        claim_eq!(
+            result,
+            Err(ContractError::Custom(CustomContractError::InvalidReveal)),
+            "Reveal not matching the commitment should fail"
+        );
+
+        // Reveal.
+        let parameter_bytes = to_bytes(&reveal);
+        ctx.set_parameter(&parameter_bytes);
+        let result: ContractResult<()> =
+            contract_reveal(&ctx, &mut host, &mut logger, &crypto_primitives);
+        claim!(result.is_ok(), "Results in rejection");
+        claim_eq!(
+            host.state()
+                .metadata_url(&new_token_id(7))
+                .map(|metadata_url| metadata_url.url),
+            Ok(format!(
+                "https://example.com/final/{}.json",
+                hex::encode(&to_bytes(&new_token_id(7))[1..])
+            )),
+            "Derived metadata URL should use the final base URL"
+        );
+        claim_eq!(
+            logger.logs,
+            vec![to_bytes(&CustomEvent::Revealed(reveal))],
+            "Expected a Revealed event"
+        );
+
+        // Revealing again is rejected.
+        let result: ContractResult<()> =
+            contract_reveal(&ctx, &mut host, &mut logger, &crypto_primitives);
+        claim_eq!(
+            result,
+            Err(ContractError::Custom(CustomContractError::AlreadyRevealed)),
+            "Revealing twice should fail"
+        );
+
+        // Nor can the base URL be replaced after the reveal.
+        let parameter_bytes = to_bytes(&"https://example.com/other/".to_string());
+        ctx.set_parameter(&parameter_bytes);
+        let result: ContractResult<()> = contract_set_base_url(&ctx, &mut host);
+        claim_eq!(
+            result,
+            Err(ContractError::Custom(
+                CustomContractError::RevealAlreadyCommitted
+            )),
+            "Setting the base URL after the reveal should fail"
+        );
+    }
+
+    /// Test a reveal cannot be committed to once the metadata of the
+    /// collection is frozen, which would hide the derived metadata URLs behind
+    /// the placeholder for good.
+    #[concordium_test]
+    fn test_commit_reveal_frozen() {
+        // Setup the context
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(ADDRESS_0);
 
         let mut logger = TestLogger::init();
         let mut state_builder = TestStateBuilder::new();
-        let state = State::empty(&mut state_builder);
+        let mut state = initial_state(&mut state_builder);
+        state.create_token(&new_token_id(7), None, 10.into(), false, None);
+        state
+            .set_base_url("https://example.com/".to_string())
+            .expect_report("Setting the base URL is expected to succeed");
         let mut host = TestHost::new(state, state_builder);
 
-        // Call the contract function.
-        let result: ContractResult<()> = contract_mint(&ctx, &mut host, &mut logger);
+        // Freeze the metadata of the collection.
+        let parameter_bytes = to_bytes(&FreezeMetadataParams::Collection);
+        ctx.set_parameter(&parameter_bytes);
+        let result: ContractResult<()> = contract_freeze_metadata(&ctx, &mut host, &mut logger);
+        claim!(result.is_ok(), "Results in rejection");
 
-        // Check the result
+        // Committing to a reveal is rejected.
+        let parameter = CommitRevealParams {
+            placeholder: MetadataUrl {
+                url: "https://example.com/placeholder.json".to_string(),
+                hash: None,
+            },
+            commitment: [1u8; 32],
+        };
+        let parameter_bytes = to_bytes(&parameter);
+        ctx.set_parameter(&parameter_bytes);
+        let result: ContractResult<()> = contract_commit_reveal(&ctx, &mut host);
+        claim_eq!(
+            result,
+            Err(ContractError::Custom(CustomContractError::MetadataFrozen)),
+            "Committing to a reveal of frozen metadata should fail"
+        );
+        claim_eq!(
+            host.state()
+                .metadata_url(&new_token_id(7))
+                .map(|metadata_url| metadata_url.url),
+            Ok(format!(
+                "https://example.com/{}",
+                hex::encode(&to_bytes(&new_token_id(7))[1..])
+            )),
+            "Derived metadata URL should still use the base URL"
+        );
+    }
+
+    /// Test the admin is handed over in two steps, moving the `Admin` role to
+    /// the new admin only once it accepts.
+    #[concordium_test]
+    fn test_transfer_admin() {
+        // Setup the context
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(ADDRESS_0);
+
+        let mut logger = TestLogger::init();
+        let mut state_builder = TestStateBuilder::new();
+        let state = initial_state(&mut state_builder);
+        let mut host = TestHost::new(state, state_builder);
+
+        // Propose the new admin.
+        let parameter_bytes = to_bytes(&Some(ADDRESS_1));
+        ctx.set_parameter(&parameter_bytes);
+        let result: ContractResult<()> = contract_transfer_admin(&ctx, &mut host, &mut logger);
         claim!(result.is_ok(), "Results in rejection");
+        claim!(
+            host.state().has_role(&ADDRESS_0, Role::Admin),
+            "The admin should keep the role until the proposal is accepted"
+        );
 
-        // Check the state
+        // Only the proposed admin can accept.
+        ctx.set_sender(Address::Account(AccountAddress([2u8; 32])));
+        let result: ContractResult<()> = contract_accept_admin(&ctx, &mut host, &mut logger);
         claim_eq!(
-            host.state().tokens.iter().count(),
-            2,
-            "Only one token is initialized"
+            result,
+            Err(ContractError::Unauthorized),
+            "Accepting by another address should fail"
         );
-        let balance0 = host
-            .state()
-            .balance(&TOKEN_0, &ADDRESS_0)
-            .expect_report("Token is expected to exist");
+
+        // Accept.
+        ctx.set_sender(ADDRESS_1);
+        let result: ContractResult<()> = contract_accept_admin(&ctx, &mut host, &mut logger);
+        claim!(result.is_ok(), "Results in rejection");
+
+        // Check the state.
         claim_eq!(
-            balance0,
-            400.into(),
-            "Initial tokens are owned by the contract instantiater"
+            host.state().admin,
+            ADDRESS_1,
+            "The admin should be replaced"
+        );
+        claim_eq!(
+            host.state().pending_admin,
+            None,
+            "The proposal should be cleared"
+        );
+        claim!(
+            host.state().has_role(&ADDRESS_1, Role::Admin)
+                && !host.state().has_role(&ADDRESS_0, Role::Admin),
+            "The Admin role should be moved to the new admin"
+        );
+        claim!(
+            host.state().has_role(&ADDRESS_1, Role::Minter)
+                && !host.state().has_role(&ADDRESS_0, Role::Minter),
+            "The Minter role should be moved to the new admin"
+        );
+        claim!(
+            host.state().has_role(&ADDRESS_1, Role::MetadataUpdater)
+                && !host.state().has_role(&ADDRESS_0, Role::MetadataUpdater),
+            "The MetadataUpdater role should be moved to the new admin"
         );
 
-        let balance1 = host
-            .state()
-            .balance(&TOKEN_1, &ADDRESS_0)
-            .expect_report("Token is expected to exist");
-        // unsafe {
+        // The previous admin can no longer mint.
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(ADDRESS_0);
+        let mut tokens = collections::BTreeMap::new();
+        tokens.insert(token_0(), ContractTokenAmount::from(1));
+        let parameter_bytes = to_bytes(&MintMoreParams {
+            owner: ADDRESS_0,
+            tokens,
+        });
+        ctx.set_parameter(&parameter_bytes);
+        let result: ContractResult<()> = contract_mint_more(&ctx, &mut host, &mut logger);
         claim_eq!(
-            balance1,
-            1.into(),
-            "Initial tokens are owned by the contract instantiater"
+            result,
+            Err(ContractError::Unauthorized),
+            "The previous admin should not be able to mint"
         );
 
-        // Check the logs
-        claim_eq!(logger.logs.len(), 4, "Exactly four events should be logged");
+        // The previous admin can no longer manage roles.
+        ctx.set_sender(ADDRESS_0);
+        let parameter_bytes = to_bytes(&RoleParams {
+            address: ADDRESS_0,
+            role: Role::Pauser,
+        });
+        ctx.set_parameter(&parameter_bytes);
+        let result: ContractResult<()> = contract_grant_role(&ctx, &mut host, &mut logger);
+        claim_eq!(
+            result,
+            Err(ContractError::Unauthorized),
+            "The previous admin should not be able to grant roles"
+        );
+
+        // Check the logs.
         claim!(
-            logger.logs.contains(&to_bytes(&Cis2Event::Mint(MintEvent {
-                owner: ADDRESS_0,
-                token_id: TOKEN_0,
-                amount: ContractTokenAmount::from(400),
-            }))),
-            "Expected an event for minting TOKEN_0"
+            logger
+                .logs
+                .contains(&to_bytes(&CustomEvent::AdminChanged(AdminChangedEvent {
+                    previous_admin: ADDRESS_0,
+                    new_admin: ADDRESS_1,
+                }))),
+            "Expected an AdminChanged event"
         );
         claim!(
-            logger.logs.contains(&to_bytes(&Cis2Event::Mint(MintEvent {
-                owner: ADDRESS_0,
-                token_id: TOKEN_1,
-                amount: ContractTokenAmount::from(1),
-            }))),
-            "Expected an event for minting TOKEN_1"
+            logger
+                .logs
+                .contains(&to_bytes(&CustomEvent::RoleRevoked(RoleEvent {
+                    address: ADDRESS_0,
+                    role: Role::Minter,
+                }))),
+            "Expected a RoleRevoked event for the Minter role"
+        );
+        claim!(
+            logger
+                .logs
+                .contains(&to_bytes(&CustomEvent::RoleGranted(RoleEvent {
+                    address: ADDRESS_1,
+                    role: Role::Minter,
+                }))),
+            "Expected a RoleGranted event for the Minter role"
+        );
+    }
+
+    /// Test migrating a state of state version 1 keeps the balances, holders
+    /// and operators, and derives the `Admin` role from the admin only.
+    #[concordium_test]
+    fn test_migrate_from_v1() {
+        let mut state_builder = TestStateBuilder::new();
+        let mut address_state = AddressStateV1 {
+            balances: state_builder.new_map(),
+            operators: state_builder.new_set(),
+        };
+        let _ = address_state.balances.insert(token_0(), 400.into());
+        address_state.operators.insert(ADDRESS_1);
+        let mut address_states = state_builder.new_map();
+        let _ = address_states.insert(ADDRESS_0, address_state);
+        let mut tokens = state_builder.new_map();
+        let _ = tokens.insert(
+            token_0(),
+            TokenStateV1 {
+                metadata_url: None,
+                supply: 400.into(),
+                max_supply: 1000.into(),
+                metadata_frozen: false,
+            },
         );
+        let mut token_holders = state_builder.new_set();
+        token_holders.insert(ADDRESS_0);
+        let mut holders = state_builder.new_map();
+        let _ = holders.insert(token_0(), token_holders);
+        let mut roles = state_builder.new_set();
+        roles.insert((ADDRESS_0, Role::Admin));
+        roles.insert((ADDRESS_1, Role::Minter));
+        let state = StateV1 {
+            version: 1,
+            collection: CollectionInfo::default(),
+            admin: ADDRESS_0,
+            pending_admin: None,
+            state: address_states,
+            tokens,
+            holders,
+            implementors: state_builder.new_map(),
+            roles,
+            metadata_frozen: false,
+            paused: false,
+            nonces_registry: state_builder.new_map(),
+            public_keys: state_builder.new_map(),
+            drops: state_builder.new_map(),
+            treasury: ACCOUNT_0,
+            redeemed_vouchers: state_builder.new_set(),
+            base_url: None,
+            placeholder: None,
+            reveal_commitment: None,
+            revealed: false,
+        };
 
+        let state = state.migrate(&mut state_builder);
+        claim_eq!(
+            state.version,
+            STATE_VERSION,
+            "State version should be migrated"
+        );
+        claim_eq!(
+            state.balance(&token_0(), &ADDRESS_0),
+            Ok(400.into()),
+            "Balance should be kept"
+        );
+        let is_holder = match state.holders.get(&token_0()) {
+            Some(holders) => holders.contains(&ADDRESS_0),
+            None => false,
+        };
+        claim!(is_holder, "Holders should be kept");
         claim!(
-            logger.logs.contains(&to_bytes(
-                &Cis2Event::TokenMetadata::<_, ContractTokenAmount>(TokenMetadataEvent {
-                    token_id: TOKEN_0,
-                    metadata_url: (TokenMetadata {
-                        url: "url".to_string(),
-                        hash: "db2ca420a0090593ac6559ff2a98ce30abfe665d7a18ff3c63883e8b98622a73"
-                            .to_string()
-                    })
-                    .to_metadata_url(),
-                })
-            )),
-            "Expected an event for token metadata for TOKEN_0"
+            state.is_operator(
+                &ADDRESS_1,
+                &ADDRESS_0,
+                Timestamp::from_timestamp_millis(u64::MAX)
+            ),
+            "Operator should be kept without expiry"
+        );
+        claim_eq!(
+            state.is_soulbound(&token_0()),
+            Ok(false),
+            "Token type should not be soulbound"
+        );
+        claim_eq!(
+            state.receive_mode,
+            ReceiveMode::Reject,
+            "Received tokens should be rejected"
+        );
+        claim!(
+            state.has_role(&ADDRESS_0, Role::Admin)
+                && !state.roles.contains(&(ADDRESS_0, Role::Admin)),
+            "Admin role should be derived from the admin only"
+        );
+        claim!(
+            state.has_role(&ADDRESS_1, Role::Minter),
+            "Other roles should be kept"
+        );
+    }
+
+    /// Test soulbound tokens cannot be transferred, not even with a zero
+    /// amount, but can be revoked by an admin.
+    #[concordium_test]
+    fn test_soulbound() {
+        let mut logger = TestLogger::init();
+        let mut state_builder = TestStateBuilder::new();
+        let mut state = initial_state(&mut state_builder);
+        state.create_token(&new_token_id(7), None, 10.into(), true, None);
+        state
+            .mint(&new_token_id(7), 1.into(), &ADDRESS_1, &mut state_builder)
+            .expect_report("Minting is expected to succeed");
+        let mut host = TestHost::new(state, state_builder);
+
+        // The view reports the token types as soulbound or not.
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(0));
+        let parameter_bytes = to_bytes(&new_token_id(7));
+        ctx.set_parameter(&parameter_bytes);
+        claim_eq!(
+            contract_is_soulbound(&ctx, &host),
+            Ok(true),
+            "Token type should be reported as soulbound"
+        );
+        let parameter_bytes = to_bytes(&token_0());
+        ctx.set_parameter(&parameter_bytes);
+        claim_eq!(
+            contract_is_soulbound(&ctx, &host),
+            Ok(false),
+            "Token type should not be reported as soulbound"
+        );
+        let parameter_bytes = to_bytes(&SupportsQueryParams {
+            queries: vec![SOULBOUND_STANDARD_IDENTIFIER.to_owned()],
+        });
+        ctx.set_parameter(&parameter_bytes);
+        let response = contract_supports(&ctx, &host).expect_report("Query should succeed");
+        claim!(
+            matches!(response.results[..], [SupportResult::Support]),
+            "The soulbound query should be reported as supported"
+        );
+
+        // The holder cannot transfer the token, whatever the amount.
+        for amount in [1, 0] {
+            let mut ctx = TestReceiveContext::empty();
+            ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(0));
+            ctx.set_sender(ADDRESS_1);
+            let transfer = Transfer {
+                token_id: new_token_id(7),
+                amount: ContractTokenAmount::from(amount),
+                from: ADDRESS_1,
+                to: Receiver::from_account(ACCOUNT_0),
+                data: AdditionalData::empty(),
+            };
+            let parameter_bytes = to_bytes(&TransferParams::from(vec![transfer]));
+            ctx.set_parameter(&parameter_bytes);
+            let result = contract_transfer(&ctx, &mut host, &mut logger);
+            claim_eq!(
+                result,
+                Err(ContractError::Custom(CustomContractError::Soulbound)),
+                "Transferring a soulbound token should fail"
+            );
+        }
+
+        // The admin can revoke the token by burning it.
+        ctx.set_sender(ADDRESS_0);
+        let parameter_bytes = to_bytes(&BurnParams {
+            owner: ADDRESS_1,
+            token_id: new_token_id(7),
+            amount: 1.into(),
+        });
+        ctx.set_parameter(&parameter_bytes);
+        let result = contract_burn(&ctx, &mut host, &mut logger);
+        claim!(result.is_ok(), "Results in rejection");
+        claim_eq!(
+            host.state().total_supply(&new_token_id(7)),
+            Ok(0.into()),
+            "The revoked token should no longer be in supply"
+        );
+        claim_eq!(
+            host.state().is_soulbound(&new_token_id(7)),
+            Ok(true),
+            "The revoked token type should stay soulbound"
+        );
+        claim_eq!(logger.logs.len(), 1, "Only the Burn event should be logged");
+    }
+
+    /// Test a spender can transfer up to its allowance, which is deducted by
+    /// each transfer.
+    #[concordium_test]
+    fn test_approve_and_transfer() {
+        let mut logger = TestLogger::init();
+        let mut state_builder = TestStateBuilder::new();
+        let state = initial_state(&mut state_builder);
+        let mut host = TestHost::new(state, state_builder);
+
+        // The owner approves the spender.
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(0));
+        ctx.set_sender(ADDRESS_0);
+        let parameter_bytes = to_bytes(&ApproveParams {
+            spender: ADDRESS_1,
+            token_id: token_0(),
+            amount: 150.into(),
+        });
+        ctx.set_parameter(&parameter_bytes);
+        let result = contract_approve(&ctx, &mut host, &mut logger);
+        claim!(result.is_ok(), "Results in rejection");
+        claim_eq!(
+            logger.logs,
+            vec![to_bytes(&CustomEvent::Approval(ApprovalEvent {
+                owner: ADDRESS_0,
+                spender: ADDRESS_1,
+                token_id: token_0(),
+                amount: 150.into(),
+            }))],
+            "Incorrect event emitted"
         );
-
         claim!(
-            logger.logs.contains(&to_bytes(
-                &Cis2Event::TokenMetadata::<_, ContractTokenAmount>(TokenMetadataEvent {
-                    token_id: TOKEN_1,
-                    metadata_url: (TokenMetadata {
-                        url: "url".to_string(),
-                        hash: "db2ca420a0090593ac6559ff2a98ce30abfe665d7a18ff3c63883e8b98622a73"
-                            .to_string()
-                    })
-                    .to_metadata_url(),
-                })
-            )),
-            "Expected an event for token metadata for TOKEN_1"
+            !host
+                .state()
+                .is_operator(&ADDRESS_1, &ADDRESS_0, Timestamp::from_timestamp_millis(0)),
+            "An allowance should not make the spender an operator"
+        );
+
+        // The spender transfers part of the allowance, then more than what is
+        // left of it.
+        for (amount, expected) in [(100, Ok(())), (100, Err(ContractError::Unauthorized))] {
+            let mut ctx = TestReceiveContext::empty();
+            ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(0));
+            ctx.set_sender(ADDRESS_1);
+            let transfer = Transfer {
+                token_id: token_0(),
+                amount: ContractTokenAmount::from(amount),
+                from: ADDRESS_0,
+                to: Receiver::from_account(ACCOUNT_1),
+                data: AdditionalData::empty(),
+            };
+            let parameter_bytes = to_bytes(&TransferParams::from(vec![transfer]));
+            ctx.set_parameter(&parameter_bytes);
+            claim_eq!(
+                contract_transfer(&ctx, &mut host, &mut logger),
+                expected,
+                "Transfer should only succeed within the allowance"
+            );
+        }
+        claim_eq!(
+            host.state().balance(&token_0(), &ADDRESS_1),
+            Ok(100.into()),
+            "Spender should have received the allowed tokens"
+        );
+
+        // The allowance of the spender is reduced by the transferred amount.
+        let parameter_bytes = to_bytes(&AllowanceOfQueryParams {
+            queries: vec![
+                AllowanceOfQuery {
+                    owner: ADDRESS_0,
+                    spender: ADDRESS_1,
+                    token_id: token_0(),
+                },
+                AllowanceOfQuery {
+                    owner: ADDRESS_0,
+                    spender: ADDRESS_1,
+                    token_id: token_1(),
+                },
+            ],
+        });
+        ctx.set_parameter(&parameter_bytes);
+        let response = contract_allowance_of(&ctx, &host).expect_report("Query should succeed");
+        claim_eq!(
+            response.0,
+            vec![50.into(), 0.into()],
+            "Allowances should be reported per token type"
         );
-        // }
     }
 
-    /// Test transfer succeeds, when `from` is the sender.
+    /// Test an operator added with an expiry is no longer an operator once the
+    /// expiry has passed.
     #[concordium_test]
-    fn test_transfer_account() {
-        // Setup the context
+    fn test_operator_expiry() {
+        let mut logger = TestLogger::init();
+        let mut state_builder = TestStateBuilder::new();
+        let state = initial_state(&mut state_builder);
+        let mut host = TestHost::new(state, state_builder);
+        let expiry = Timestamp::from_timestamp_millis(2_000);
+
+        // The owner adds an operator expiring at `expiry`.
         let mut ctx = TestReceiveContext::empty();
         ctx.set_sender(ADDRESS_0);
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(1_000));
+        let parameter_bytes = to_bytes(&UpdateOperatorWithExpiryParams {
+            updates: UpdateOperatorParams(vec![UpdateOperator {
+                operator: ADDRESS_1,
+                update: OperatorUpdate::Add,
+            }]),
+            expiry,
+        });
+        ctx.set_parameter(&parameter_bytes);
+        let result = contract_update_operator_with_expiry(&ctx, &mut host, &mut logger);
+        claim!(result.is_ok(), "Results in rejection");
 
-        // and parameter.
+        // An expiry which has already passed is rejected.
+        let parameter_bytes = to_bytes(&UpdateOperatorWithExpiryParams {
+            updates: UpdateOperatorParams(vec![UpdateOperator {
+                operator: ADDRESS_1,
+                update: OperatorUpdate::Add,
+            }]),
+            expiry: Timestamp::from_timestamp_millis(1_000),
+        });
+        ctx.set_parameter(&parameter_bytes);
+        let result = contract_update_operator_with_expiry(&ctx, &mut host, &mut logger);
+        claim_eq!(
+            result,
+            Err(ContractError::Custom(CustomContractError::InvalidExpiry)),
+            "An expiry in the past should be rejected"
+        );
+
+        // Before the expiry, the operator is listed.
+        let parameter_bytes = to_bytes(&OperatorsOfParams {
+            owner: ADDRESS_0,
+            cursor: None,
+            limit: 10,
+        });
+        ctx.set_parameter(&parameter_bytes);
+        let response = contract_operators_of(&ctx, &host).expect_report("Query should succeed");
+        claim_eq!(
+            response.operators,
+            vec![(ADDRESS_1, Some(expiry))],
+            "Operator should be listed with its expiry"
+        );
+
+        // Once the expiry has passed, the operator can no longer transfer and is
+        // no longer listed.
+        ctx.set_metadata_slot_time(expiry);
+        let response = contract_operators_of(&ctx, &host).expect_report("Query should succeed");
+        claim!(
+            response.operators.is_empty(),
+            "The expired operator is listed"
+        );
+
+        ctx.set_sender(ADDRESS_1);
         let transfer = Transfer {
-            token_id: TOKEN_0,
+            token_id: token_0(),
             amount: ContractTokenAmount::from(100),
             from: ADDRESS_0,
             to: Receiver::from_account(ACCOUNT_1),
             data: AdditionalData::empty(),
         };
-        let parameter = TransferParams::from(vec![transfer]);
-        let parameter_bytes = to_bytes(&parameter);
+        let parameter_bytes = to_bytes(&TransferParams::from(vec![transfer]));
         ctx.set_parameter(&parameter_bytes);
+        let result = contract_transfer(&ctx, &mut host, &mut logger);
+        claim_eq!(
+            result,
+            Err(ContractError::Unauthorized),
+            "Transfer by an expired operator should fail"
+        );
+    }
 
+    /// Test the royalties set at mint, or the royalties of the collection
+    /// otherwise, are reported by `royaltyInfo`.
+    #[concordium_test]
+    fn test_royalty_info() {
         let mut logger = TestLogger::init();
         let mut state_builder = TestStateBuilder::new();
-        let state = initial_state(&mut state_builder);
+        let mut state = initial_state(&mut state_builder);
+        state.royalties = vec![Royalty {
+            recipient: ACCOUNT_0,
+            basis_points: 250,
+        }];
         let mut host = TestHost::new(state, state_builder);
 
-        // Call the contract function.
-        let result: ContractResult<()> = contract_transfer(&ctx, &mut host, &mut logger);
-        // Check the result.
-        claim!(result.is_ok(), "Results in rejection");
-
-        // Check the state.
-        let balance0 = host
-            .state()
-            .balance(&TOKEN_0, &ADDRESS_0)
-            .expect_report("Token is expected to exist");
-        let balance1 = host
-            .state()
-            .balance(&TOKEN_0, &ADDRESS_1)
-            .expect_report("Token is expected to exist");
-        claim_eq!(
-            balance0,
-            300.into(),
-            "Token owner balance should be decreased by the transferred amount."
-        );
+        // Mint a token type with royalties of its own.
+        let mint_params = |royalties| {
+            let mut tokens = collections::BTreeMap::new();
+            tokens.insert(
+                new_token_id(7),
+                TokenMintParams {
+                    metadata: Some(TokenMetadata {
+                        url: "url".to_string(),
+                        hash: None,
+                    }),
+                    max_supply: 1.into(),
+                    amount: 1.into(),
+                    soulbound: false,
+                    royalties: Some(royalties),
+                },
+            );
+            to_bytes(&MintParams {
+                owner: ADDRESS_0,
+                tokens,
+            })
+        };
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(ADDRESS_0);
+        let parameter_bytes = mint_params(vec![Royalty {
+            recipient: ACCOUNT_1,
+            basis_points: MAX_BASIS_POINTS + 1,
+        }]);
+        ctx.set_parameter(&parameter_bytes);
         claim_eq!(
-            balance1,
-            100.into(),
-            "Token receiver balance should be increased by the transferred amount"
+            contract_mint(&ctx, &mut host, &mut logger),
+            Err(ContractError::Custom(CustomContractError::InvalidRoyalty)),
+            "Royalties above the sale price should be rejected"
         );
+        let parameter_bytes = mint_params(vec![
+            Royalty {
+                recipient: ACCOUNT_1,
+                basis_points: 500,
+            },
+            Royalty {
+                recipient: ACCOUNT_0,
+                basis_points: 100,
+            },
+        ]);
+        ctx.set_parameter(&parameter_bytes);
+        let result = contract_mint(&ctx, &mut host, &mut logger);
+        claim!(result.is_ok(), "Results in rejection");
 
-        // Check the logs.
-        claim_eq!(logger.logs.len(), 1, "Only one event should be logged");
-        claim_eq!(
-            logger.logs[0],
-            to_bytes(&Cis2Event::Transfer(TransferEvent {
-                from: ADDRESS_0,
-                to: ADDRESS_1,
-                token_id: TOKEN_0,
-                amount: ContractTokenAmount::from(100),
-            })),
-            "Incorrect event emitted"
-        )
+        // The token type pays its own royalties, while the others pay the
+        // royalties of the collection.
+        let sale_price = Amount::from_micro_ccd(1_000);
+        for (token_id, expected) in [
+            (
+                new_token_id(7),
+                vec![
+                    (ACCOUNT_1, Amount::from_micro_ccd(50)),
+                    (ACCOUNT_0, Amount::from_micro_ccd(10)),
+                ],
+            ),
+            (token_0(), vec![(ACCOUNT_0, Amount::from_micro_ccd(25))]),
+        ] {
+            let mut ctx = TestReceiveContext::empty();
+            let parameter_bytes = to_bytes(&RoyaltyInfoParams {
+                token_id,
+                sale_price,
+            });
+            ctx.set_parameter(&parameter_bytes);
+            let response = contract_royalty_info(&ctx, &host).expect_report("Query should succeed");
+            claim_eq!(
+                response.payments,
+                expected,
+                "Royalty payments should match the applicable royalties"
+            );
+        }
     }
 
-    /// Test transfer token fails, when sender is neither the owner or an
-    /// operator of the owner.
-    #[concordium_test]
-    fn test_transfer_not_authorized() {
-        // Setup the context
-        let mut ctx = TestReceiveContext::empty();
-        ctx.set_sender(ADDRESS_1);
+    /// The contract of the CIS2 tokens received in the tests.
+    const TOKEN_CONTRACT: ContractAddress = ContractAddress {
+        index: 1,
+        subindex: 0,
+    };
 
-        // and parameter.
-        let transfer = Transfer {
-            from: ADDRESS_0,
-            to: Receiver::from_account(ACCOUNT_1),
-            token_id: TOKEN_0,
-            amount: ContractTokenAmount::from(100),
+    /// The parameter of `onReceivingCIS2` for receiving 10 tokens from
+    /// `ADDRESS_1`.
+    fn on_receiving_params() -> OnReceivingParams {
+        OnReceivingCis2Params {
+            token_id: TokenIdVec(vec![42]),
+            amount: 10.into(),
+            from: ADDRESS_1,
             data: AdditionalData::empty(),
-        };
-        let parameter = TransferParams::from(vec![transfer]);
-        let parameter_bytes = to_bytes(&parameter);
+        }
+    }
+
+    /// Mock the `transfer` function of the token contract, accepting any
+    /// transfer.
+    fn mock_transfer(
+        _parameter: Parameter,
+        _amount: Amount,
+        _balance: &mut Amount,
+        _state: &mut State<TestStateApi>,
+    ) -> Result<(bool, ()), CallContractError<()>> {
+        Ok((false, ()))
+    }
+
+    /// Test received tokens are rejected in the `Reject` mode.
+    #[concordium_test]
+    fn test_receive_reject() {
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(Address::Contract(TOKEN_CONTRACT));
+        let parameter_bytes = to_bytes(&on_receiving_params());
         ctx.set_parameter(&parameter_bytes);
 
         let mut logger = TestLogger::init();
@@ -1116,152 +7577,132 @@ mod tests {
         let state = initial_state(&mut state_builder);
         let mut host = TestHost::new(state, state_builder);
 
-        // Call the contract function.
-        let result: ContractResult<()> = contract_transfer(&ctx, &mut host, &mut logger);
-        // Check the result.
-        let err = result.expect_err_report("Expected to fail");
+        let result = contract_on_cis2_received(&ctx, &mut host, &mut logger);
         claim_eq!(
-            err,
-            ContractError::Unauthorized,
-            "Error is expected to be Unauthorized"
-        )
+            result,
+            Err(ContractError::Custom(CustomContractError::ReceiveRejected)),
+            "Receiving tokens should be rejected"
+        );
     }
 
-    /// Test transfer succeeds when sender is not the owner, but is an operator
-    /// of the owner.
+    /// Test received tokens are kept in custody for the address they came from
+    /// in the `Custody` mode, which can withdraw them.
     #[concordium_test]
-    fn test_operator_transfer() {
-        // Setup the context
+    fn test_receive_custody_and_withdraw() {
         let mut ctx = TestReceiveContext::empty();
-        ctx.set_sender(ADDRESS_1);
-
-        // and parameter.
-        let transfer = Transfer {
-            from: ADDRESS_0,
-            to: Receiver::from_account(ACCOUNT_1),
-            token_id: TOKEN_0,
-            amount: ContractTokenAmount::from(100),
-            data: AdditionalData::empty(),
-        };
-        let parameter = TransferParams::from(vec![transfer]);
-        let parameter_bytes = to_bytes(&parameter);
+        ctx.set_sender(Address::Contract(TOKEN_CONTRACT));
+        ctx.set_self_address(ContractAddress {
+            index: 0,
+            subindex: 0,
+        });
+        let parameter_bytes = to_bytes(&on_receiving_params());
         ctx.set_parameter(&parameter_bytes);
 
         let mut logger = TestLogger::init();
         let mut state_builder = TestStateBuilder::new();
         let mut state = initial_state(&mut state_builder);
-        state.add_operator(&ADDRESS_0, &ADDRESS_1, &mut state_builder);
+        state.receive_mode = ReceiveMode::Custody;
         let mut host = TestHost::new(state, state_builder);
+        host.setup_mock_entrypoint(
+            TOKEN_CONTRACT,
+            OwnedEntrypointName::new_unchecked("transfer".to_string()),
+            MockFn::new_v1(mock_transfer),
+        );
 
-        // Call the contract function.
-        let result: ContractResult<()> = contract_transfer(&ctx, &mut host, &mut logger);
-
-        // Check the result.
+        // Receive the tokens.
+        let result = contract_on_cis2_received(&ctx, &mut host, &mut logger);
         claim!(result.is_ok(), "Results in rejection");
-
-        // Check the state.
-        let balance0 = host
-            .state()
-            .balance(&TOKEN_0, &ADDRESS_0)
-            .expect_report("Token is expected to exist");
-        let balance1 = host
-            .state()
-            .balance(&TOKEN_0, &ADDRESS_1)
-            .expect_report("Token is expected to exist");
+        let deposit = DepositKey {
+            token_contract: TOKEN_CONTRACT,
+            token_id: TokenIdVec(vec![42]),
+            depositor: ADDRESS_1,
+        };
         claim_eq!(
-            balance0,
-            300.into(),
-            "Token owner balance should be decreased by the transferred amount"
+            host.state().deposit_of(&deposit),
+            10.into(),
+            "Received tokens should be kept in custody for the depositor"
         );
         claim_eq!(
-            balance1,
-            100.into(),
-            "Token receiver balance should be increased by the transferred amount"
+            logger.logs,
+            vec![to_bytes(&CustomEvent::Deposit(DepositEvent {
+                deposit: deposit.clone(),
+                amount: 10.into(),
+            }))],
+            "Incorrect event emitted"
         );
 
-        // Check the logs.
-        claim_eq!(logger.logs.len(), 1, "Only one event should be logged");
+        // Only the depositor can withdraw the tokens.
+        let parameter_bytes = to_bytes(&WithdrawParams {
+            token_contract: TOKEN_CONTRACT,
+            token_id: TokenIdVec(vec![42]),
+            amount: 4.into(),
+            to: Receiver::from_account(ACCOUNT_0),
+        });
+        ctx.set_parameter(&parameter_bytes);
+        ctx.set_sender(ADDRESS_0);
         claim_eq!(
-            logger.logs[0],
-            to_bytes(&Cis2Event::Transfer(TransferEvent {
-                from: ADDRESS_0,
-                to: ADDRESS_1,
-                token_id: TOKEN_0,
-                amount: ContractTokenAmount::from(100),
+            contract_withdraw(&ctx, &mut host, &mut logger),
+            Err(ContractError::InsufficientFunds),
+            "Withdrawing the tokens of another depositor should fail"
+        );
+        ctx.set_sender(ADDRESS_1);
+        let result = contract_withdraw(&ctx, &mut host, &mut logger);
+        claim!(result.is_ok(), "Results in rejection");
+        claim_eq!(
+            host.state().deposit_of(&deposit),
+            6.into(),
+            "Withdrawn tokens should be deducted from the deposit"
+        );
+        claim_eq!(
+            logger.logs[1],
+            to_bytes(&CustomEvent::Withdraw(WithdrawEvent {
+                deposit,
+                amount: 4.into(),
+                to: ADDRESS_0,
             })),
             "Incorrect event emitted"
-        )
+        );
     }
 
-    /// Test adding an operator succeeds and the appropriate event is logged.
+    /// Test received tokens are transferred to the admin in the
+    /// `ForwardToAdmin` mode.
     #[concordium_test]
-    fn test_add_operator() {
-        // Setup the context
+    fn test_receive_forward_to_admin() {
         let mut ctx = TestReceiveContext::empty();
-        ctx.set_sender(ADDRESS_0);
-
-        // and parameter.
-        let update = UpdateOperator {
-            operator: ADDRESS_1,
-            update: OperatorUpdate::Add,
-        };
-        let parameter = UpdateOperatorParams(vec![update]);
-        let parameter_bytes = to_bytes(&parameter);
+        ctx.set_sender(Address::Contract(TOKEN_CONTRACT));
+        ctx.set_self_address(ContractAddress {
+            index: 0,
+            subindex: 0,
+        });
+        let parameter_bytes = to_bytes(&on_receiving_params());
         ctx.set_parameter(&parameter_bytes);
 
         let mut logger = TestLogger::init();
         let mut state_builder = TestStateBuilder::new();
-        let state = initial_state(&mut state_builder);
+        let mut state = initial_state(&mut state_builder);
+        state.receive_mode = ReceiveMode::ForwardToAdmin;
         let mut host = TestHost::new(state, state_builder);
+        host.setup_mock_entrypoint(
+            TOKEN_CONTRACT,
+            OwnedEntrypointName::new_unchecked("transfer".to_string()),
+            MockFn::new_v1(mock_transfer),
+        );
 
-        // Call the contract function.
-        let result: ContractResult<()> = contract_update_operator(&ctx, &mut host, &mut logger);
-
-        // Check the result.
+        let result = contract_on_cis2_received(&ctx, &mut host, &mut logger);
         claim!(result.is_ok(), "Results in rejection");
-
-        // Check the state.
-        let is_operator = host.state().is_operator(&ADDRESS_1, &ADDRESS_0);
-        claim!(is_operator, "Account should be an operator");
-
-        // Checking that `ADDRESS_1` is an operator in the query response of the
-        // `contract_operator_of` function as well.
-        // Setup parameter.
-        let operator_of_query = OperatorOfQuery {
-            address: ADDRESS_1,
-            owner: ADDRESS_0,
-        };
-
-        let operator_of_query_vector = OperatorOfQueryParams {
-            queries: vec![operator_of_query],
-        };
-        let parameter_bytes = to_bytes(&operator_of_query_vector);
-
-        ctx.set_parameter(&parameter_bytes);
-
-        // Checking the return value of the `contract_operator_of` function
-        let result: ContractResult<OperatorOfQueryResponse> = contract_operator_of(&ctx, &host);
-
-        claim_eq!(
-            result.expect_report("Failed getting result value").0,
-            [true],
-            "Account should be an operator in the query response"
+        claim!(
+            host.state().deposits.iter().next().is_none(),
+            "Forwarded tokens should not be kept in custody"
         );
-
-        // Check the logs.
-        claim_eq!(logger.logs.len(), 1, "One event should be logged");
         claim_eq!(
-            logger.logs[0],
-            to_bytes(
-                &Cis2Event::<ContractTokenId, ContractTokenAmount>::UpdateOperator(
-                    UpdateOperatorEvent {
-                        owner: ADDRESS_0,
-                        operator: ADDRESS_1,
-                        update: OperatorUpdate::Add,
-                    }
-                )
-            ),
+            logger.logs,
+            vec![to_bytes(&CustomEvent::Forward(ForwardEvent {
+                token_contract: TOKEN_CONTRACT,
+                token_id: TokenIdVec(vec![42]),
+                amount: 10.into(),
+                to: ADDRESS_0,
+            }))],
             "Incorrect event emitted"
-        )
+        );
     }
 }
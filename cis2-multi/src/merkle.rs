@@ -0,0 +1,108 @@
+//! Merkle trees over allowlist entries.
+//!
+//! # Description
+//! An allowlist is a list of accounts together with the amount of tokens each
+//! account is allowed to mint. Only the root of the Merkle tree over the
+//! allowlist is stored on-chain, and an account proves it is on the allowlist
+//! by providing the hashes of the siblings on the path from its leaf to the
+//! root.
+//!
+//! A leaf is the SHA256 hash of the serialized `(account, allowance)` pair.
+//! The two children of a node are sorted before being hashed together, such
+//! that a proof does not need to contain the position of each sibling.
+//!
+//! The `MerkleTree` builder is meant for tests and off-chain tools creating
+//! the root and the proofs of an allowlist.
+
+use concordium_cis2::TokenAmountU64;
+use concordium_std::*;
+
+/// A SHA256 hash, a node of a Merkle tree.
+pub type MerkleHash = [u8; 32];
+
+/// Hash an allowlist entry into a leaf of a Merkle tree.
+pub fn hash_leaf(
+    crypto_primitives: &impl HasCryptoPrimitives,
+    account: &AccountAddress,
+    allowance: TokenAmountU64,
+) -> MerkleHash {
+    crypto_primitives
+        .hash_sha2_256(&to_bytes(&(*account, allowance)))
+        .0
+}
+
+/// Hash two nodes of a Merkle tree into their parent.
+/// The nodes are sorted first, so the order of the arguments does not matter.
+pub fn hash_pair(
+    crypto_primitives: &impl HasCryptoPrimitives,
+    left: &MerkleHash,
+    right: &MerkleHash,
+) -> MerkleHash {
+    let (first, second) = if left <= right {
+        (left, right)
+    } else {
+        (right, left)
+    };
+    let mut bytes = [0u8; 64];
+    bytes[..32].copy_from_slice(first);
+    bytes[32..].copy_from_slice(second);
+    crypto_primitives.hash_sha2_256(&bytes).0
+}
+
+/// Check that a leaf is part of the Merkle tree with the given root.
+pub fn verify(
+    crypto_primitives: &impl HasCryptoPrimitives,
+    root: &MerkleHash,
+    leaf: MerkleHash,
+    proof: &[MerkleHash],
+) -> bool {
+    let computed = proof.iter().fold(leaf, |node, sibling| {
+        hash_pair(crypto_primitives, &node, sibling)
+    });
+    computed == *root
+}
+
+/// A Merkle tree over a list of leaves, for building roots and proofs.
+/// A node without a sibling is moved up to the next layer unchanged.
+pub struct MerkleTree {
+    /// The layers of the tree, starting with the leaves and ending with the
+    /// root.
+    layers: Vec<Vec<MerkleHash>>,
+}
+
+impl MerkleTree {
+    /// Build the tree over a non-empty list of leaves.
+    pub fn new(crypto_primitives: &impl HasCryptoPrimitives, leaves: Vec<MerkleHash>) -> Self {
+        let mut layers = vec![leaves];
+        while layers[layers.len() - 1].len() > 1 {
+            let next = layers[layers.len() - 1]
+                .chunks(2)
+                .map(|pair| match pair {
+                    [left, right] => hash_pair(crypto_primitives, left, right),
+                    [single] => *single,
+                    _ => unreachable!("Chunks contain one or two nodes"),
+                })
+                .collect();
+            layers.push(next);
+        }
+        MerkleTree { layers }
+    }
+
+    /// The root of the tree.
+    pub fn root(&self) -> MerkleHash {
+        self.layers[self.layers.len() - 1][0]
+    }
+
+    /// The proof that the leaf at the given index is part of the tree.
+    pub fn proof(&self, mut index: usize) -> Vec<MerkleHash> {
+        let mut proof = Vec::new();
+        for layer in &self.layers[..self.layers.len() - 1] {
+            let sibling = index ^ 1;
+            if sibling < layer.len() {
+                proof.push(layer[sibling]);
+            }
+            index /= 2;
+        }
+        proof
+    }
+}
@@ -0,0 +1,291 @@
+//! Tests sponsored transactions through `permit`, which checks the signatures
+//! against the keys of the signer account, using the smart contract testing
+//! library.
+//!
+//! The tests require the contract module to be built first, hence they are
+//! ignored by default and run explicitly as described in the README:
+//! `cargo concordium build --out concordium-out/module.wasm.v1`
+use cis2_multi::{new_token_id, ContractTokenId, TokenMetadata};
+use concordium_cis2::{Transfer, *};
+use concordium_smart_contract_testing::*;
+use concordium_std::{collections::BTreeMap, AccountSignatures, Deserial, Serial};
+
+const ACC_SIGNER: AccountAddress = AccountAddress([0u8; 32]);
+const ACC_SPONSOR: AccountAddress = AccountAddress([1u8; 32]);
+const ACC_INITIAL_BALANCE: Amount = Amount::from_ccd(10_000);
+const SIGNER: Signer = Signer::with_one_key();
+const ENERGY: Energy = Energy { energy: 100_000 };
+
+/// The reject code of the `WrongSignature` error of the contract.
+const WRONG_SIGNATURE: i32 = -13;
+/// The reject code of the `NonceMismatch` error of the contract.
+const NONCE_MISMATCH: i32 = -14;
+
+/// The parameter of `init`, mirroring `InitParams` of the contract.
+#[derive(Serial)]
+struct InitParams {
+    name: String,
+    symbol: String,
+    metadata: Option<TokenMetadata>,
+    admin: Option<Address>,
+    base_url: Option<String>,
+    royalties: Vec<(AccountAddress, u16)>,
+    /// The tag of the `ReceiveMode`.
+    receive_mode: u8,
+}
+
+/// The parameter of `mint`, mirroring `TokenMintParams` of the contract.
+#[derive(Serial)]
+struct TokenMintParams {
+    metadata: Option<TokenMetadata>,
+    max_supply: TokenAmountU64,
+    amount: TokenAmountU64,
+    soulbound: bool,
+    royalties: Option<Vec<(AccountAddress, u16)>>,
+}
+
+/// The parameter of `mint`, mirroring `MintParams` of the contract.
+#[derive(Serial)]
+struct MintParams {
+    owner: Address,
+    tokens: BTreeMap<ContractTokenId, TokenMintParams>,
+}
+
+/// The signed message, mirroring `PermitMessage` of the contract.
+#[derive(Serial, Clone)]
+struct PermitMessage {
+    contract_address: ContractAddress,
+    nonce: u64,
+    timestamp: Timestamp,
+    entry_point: OwnedEntrypointName,
+    #[concordium(size_length = 2)]
+    payload: Vec<u8>,
+}
+
+/// The parameter of `permit`, mirroring `PermitParam` of the contract.
+#[derive(Serial)]
+struct PermitParam {
+    signature: AccountSignatures,
+    signer: AccountAddress,
+    message: PermitMessage,
+}
+
+/// The parameter of `viewMessageHash`, mirroring `ViewMessageHashParams` of
+/// the contract.
+#[derive(Serial)]
+struct ViewMessageHashParams {
+    signer: AccountAddress,
+    message: PermitMessage,
+}
+
+/// Deploy the module, initialize the contract and mint tokens to the signer,
+/// whose account has the given keys.
+fn initialize(keys: &AccountKeys) -> (Chain, ContractAddress) {
+    let mut chain = Chain::new();
+    chain.create_account(Account::new_with_keys(
+        ACC_SIGNER,
+        AccountBalance::new(ACC_INITIAL_BALANCE, Amount::zero(), Amount::zero())
+            .expect("Valid balance"),
+        keys.into(),
+    ));
+    chain.create_account(Account::new(ACC_SPONSOR, ACC_INITIAL_BALANCE));
+
+    let module = module_load_v1("concordium-out/module.wasm.v1").expect("Module exists");
+    let deployment = chain
+        .module_deploy_v1(SIGNER, ACC_SIGNER, module)
+        .expect("Deploying a valid module");
+
+    let init = chain
+        .contract_init(
+            SIGNER,
+            ACC_SIGNER,
+            ENERGY,
+            InitContractPayload {
+                amount: Amount::zero(),
+                mod_ref: deployment.module_reference,
+                init_name: OwnedContractName::new_unchecked("init_CIS2-Multi".to_string()),
+                param: OwnedParameter::from_serial(&InitParams {
+                    name: "Collection".to_string(),
+                    symbol: "COL".to_string(),
+                    metadata: None,
+                    admin: None,
+                    base_url: Some("https://example.com/{id}.json".to_string()),
+                    royalties: Vec::new(),
+                    receive_mode: 0,
+                })
+                .expect("Parameter within size bounds"),
+            },
+        )
+        .expect("Initialization succeeds");
+    let contract = init.contract_address;
+
+    let mut tokens = BTreeMap::new();
+    tokens.insert(
+        new_token_id(1),
+        TokenMintParams {
+            metadata: None,
+            max_supply: TokenAmountU64(1000),
+            amount: TokenAmountU64(400),
+            soulbound: false,
+            royalties: None,
+        },
+    );
+    update(
+        &mut chain,
+        contract,
+        ACC_SIGNER,
+        "mint",
+        &MintParams {
+            owner: Address::Account(ACC_SIGNER),
+            tokens,
+        },
+    )
+    .expect("Minting succeeds");
+
+    (chain, contract)
+}
+
+/// Call an entrypoint of the contract as an account.
+fn update(
+    chain: &mut Chain,
+    contract: ContractAddress,
+    sender: AccountAddress,
+    entrypoint: &str,
+    parameter: &impl Serial,
+) -> Result<ContractInvokeSuccess, ContractInvokeError> {
+    chain.contract_update(
+        SIGNER,
+        sender,
+        Address::Account(sender),
+        ENERGY,
+        UpdateContractPayload {
+            amount: Amount::zero(),
+            address: contract,
+            receive_name: OwnedReceiveName::new_unchecked(format!("CIS2-Multi.{}", entrypoint)),
+            message: OwnedParameter::from_serial(parameter).expect("Parameter within size bounds"),
+        },
+    )
+}
+
+/// Call a view of the contract and parse the return value.
+fn view<R: Deserial>(
+    chain: &Chain,
+    contract: ContractAddress,
+    entrypoint: &str,
+    parameter: &impl Serial,
+) -> R {
+    chain
+        .contract_invoke(
+            ACC_SPONSOR,
+            Address::Account(ACC_SPONSOR),
+            ENERGY,
+            UpdateContractPayload {
+                amount: Amount::zero(),
+                address: contract,
+                receive_name: OwnedReceiveName::new_unchecked(format!("CIS2-Multi.{}", entrypoint)),
+                message: OwnedParameter::from_serial(parameter)
+                    .expect("Parameter within size bounds"),
+            },
+        )
+        .expect("Invoking the view succeeds")
+        .parse_return_value()
+        .expect("Valid return value")
+}
+
+/// A message transferring 100 tokens from the signer to the sponsor.
+fn transfer_message(contract: ContractAddress, nonce: u64) -> PermitMessage {
+    let transfer = Transfer {
+        token_id: new_token_id(1),
+        amount: TokenAmountU64(100),
+        from: Address::Account(ACC_SIGNER),
+        to: Receiver::from_account(ACC_SPONSOR),
+        data: AdditionalData::empty(),
+    };
+    PermitMessage {
+        contract_address: contract,
+        nonce,
+        timestamp: Timestamp::from_timestamp_millis(u64::MAX),
+        entry_point: OwnedEntrypointName::new_unchecked("transfer".to_string()),
+        payload: to_bytes(&TransferParams::from(vec![transfer])),
+    }
+}
+
+/// Sign a message with the given keys, over the hash reported by
+/// `viewMessageHash`.
+fn sign(
+    chain: &Chain,
+    contract: ContractAddress,
+    keys: &AccountKeys,
+    message: &PermitMessage,
+) -> AccountSignatures {
+    let message_hash: [u8; 32] = view(
+        chain,
+        contract,
+        "viewMessageHash",
+        &ViewMessageHashParams {
+            signer: ACC_SIGNER,
+            message: message.clone(),
+        },
+    );
+    keys.sign_message(&message_hash)
+}
+
+/// Query the balance of the sponsor.
+fn sponsor_balance(chain: &Chain, contract: ContractAddress) -> TokenAmountU64 {
+    let response: BalanceOfQueryResponse<TokenAmountU64> = view(
+        chain,
+        contract,
+        "balanceOf",
+        &BalanceOfQueryParams {
+            queries: vec![BalanceOfQuery {
+                token_id: new_token_id(1),
+                address: Address::Account(ACC_SPONSOR),
+            }],
+        },
+    );
+    response.0[0]
+}
+
+/// Test a transfer signed with the keys of the signer account is executed
+/// when submitted by a sponsor, and cannot be replayed.
+#[test]
+#[ignore = "requires the built modules, see the README"]
+fn test_permit_transfer() {
+    let keys = AccountKeys::singleton(&mut rand::thread_rng());
+    let (mut chain, contract) = initialize(&keys);
+
+    let message = transfer_message(contract, 0);
+    let parameter = PermitParam {
+        signature: sign(&chain, contract, &keys, &message),
+        signer: ACC_SIGNER,
+        message,
+    };
+    update(&mut chain, contract, ACC_SPONSOR, "permit", &parameter).expect("Permit succeeds");
+    assert_eq!(sponsor_balance(&chain, contract), TokenAmountU64(100));
+
+    let error = update(&mut chain, contract, ACC_SPONSOR, "permit", &parameter)
+        .expect_err("Replaying a message should fail");
+    assert_eq!(error.reject_code(), Some(NONCE_MISMATCH));
+    assert_eq!(sponsor_balance(&chain, contract), TokenAmountU64(100));
+}
+
+/// Test a message signed with keys other than those of the signer account is
+/// rejected.
+#[test]
+#[ignore = "requires the built modules, see the README"]
+fn test_permit_wrong_signature() {
+    let keys = AccountKeys::singleton(&mut rand::thread_rng());
+    let (mut chain, contract) = initialize(&keys);
+
+    let other_keys = AccountKeys::singleton(&mut rand::thread_rng());
+    let message = transfer_message(contract, 0);
+    let parameter = PermitParam {
+        signature: sign(&chain, contract, &other_keys, &message),
+        signer: ACC_SIGNER,
+        message,
+    };
+    let error = update(&mut chain, contract, ACC_SPONSOR, "permit", &parameter)
+        .expect_err("A message signed by another account should fail");
+    assert_eq!(error.reject_code(), Some(WRONG_SIGNATURE));
+    assert_eq!(sponsor_balance(&chain, contract), TokenAmountU64(0));
+}
@@ -0,0 +1,331 @@
+//! Tests upgrading a populated instance of the contract with state version 1
+//! to the current module, using the smart contract testing library.
+//!
+//! The tests require the current contract module and the module of state
+//! version 1 to be built first, hence they are ignored by default and run
+//! explicitly as described in the README:
+//! `concordium-out/module.wasm.v1` and `concordium-out/module-v1.wasm.v1`.
+use cis2_multi::{new_token_id, ContractTokenId, TokenMetadata};
+use concordium_cis2::{Transfer, *};
+use concordium_smart_contract_testing::*;
+use concordium_std::{collections::BTreeMap, Deserial, Serial};
+
+const ACC_ADMIN: AccountAddress = AccountAddress([0u8; 32]);
+const ACC_HOLDER: AccountAddress = AccountAddress([1u8; 32]);
+const ACC_INITIAL_BALANCE: Amount = Amount::from_ccd(10_000);
+const SIGNER: Signer = Signer::with_one_key();
+const ENERGY: Energy = Energy { energy: 100_000 };
+
+/// The state version of the current module.
+const STATE_VERSION: u32 = 2;
+
+/// The parameter of `init` of state version 1, mirroring `InitParams` of the
+/// contract at that version.
+#[derive(Serial)]
+struct InitParamsV1 {
+    name: String,
+    symbol: String,
+    metadata: Option<TokenMetadata>,
+    admin: Option<Address>,
+    base_url: Option<String>,
+}
+
+/// The parameter of `mint` of state version 1, mirroring `TokenMintParams`
+/// of the contract at that version.
+#[derive(Serial)]
+struct TokenMintParamsV1 {
+    metadata: Option<TokenMetadata>,
+    max_supply: TokenAmountU64,
+    amount: TokenAmountU64,
+}
+
+/// The parameter of `mint` of state version 1, mirroring `MintParams` of the
+/// contract at that version.
+#[derive(Serial)]
+struct MintParamsV1 {
+    owner: Address,
+    tokens: BTreeMap<ContractTokenId, TokenMintParamsV1>,
+}
+
+/// The parameter of `upgrade`, mirroring `UpgradeParams` of the contract.
+#[derive(Serial)]
+struct UpgradeParams {
+    module: ModuleReference,
+    migrate: Option<(OwnedEntrypointName, OwnedParameter)>,
+}
+
+/// Deploy a module and return its reference.
+fn deploy(chain: &mut Chain, path: &str) -> ModuleReference {
+    let module = module_load_v1(path).expect("Module exists");
+    chain
+        .module_deploy_v1(SIGNER, ACC_ADMIN, module)
+        .expect("Deploying a valid module")
+        .module_reference
+}
+
+/// Deploy the module of state version 1 and the current module, initialize
+/// the contract from the module of state version 1, mint tokens to the admin,
+/// transfer some of them to the holder and enable the admin as an operator of
+/// the holder.
+fn initialize_v1_instance() -> (Chain, ModuleReference, ContractAddress) {
+    let mut chain = Chain::new();
+    chain.create_account(Account::new(ACC_ADMIN, ACC_INITIAL_BALANCE));
+    chain.create_account(Account::new(ACC_HOLDER, ACC_INITIAL_BALANCE));
+
+    let module_v1 = deploy(&mut chain, "concordium-out/module-v1.wasm.v1");
+    let module = deploy(&mut chain, "concordium-out/module.wasm.v1");
+
+    let init = chain
+        .contract_init(
+            SIGNER,
+            ACC_ADMIN,
+            ENERGY,
+            InitContractPayload {
+                amount: Amount::zero(),
+                mod_ref: module_v1,
+                init_name: OwnedContractName::new_unchecked("init_CIS2-Multi".to_string()),
+                param: OwnedParameter::from_serial(&InitParamsV1 {
+                    name: "Collection".to_string(),
+                    symbol: "COL".to_string(),
+                    metadata: None,
+                    admin: None,
+                    base_url: Some("https://example.com/{id}.json".to_string()),
+                })
+                .expect("Parameter within size bounds"),
+            },
+        )
+        .expect("Initialization succeeds");
+    let contract = init.contract_address;
+    assert_eq!(state_version(&chain, contract), 1);
+
+    let mut tokens = BTreeMap::new();
+    tokens.insert(
+        new_token_id(1),
+        TokenMintParamsV1 {
+            metadata: None,
+            max_supply: TokenAmountU64(1000),
+            amount: TokenAmountU64(400),
+        },
+    );
+    update(
+        &mut chain,
+        contract,
+        ACC_ADMIN,
+        "mint",
+        &MintParamsV1 {
+            owner: Address::Account(ACC_ADMIN),
+            tokens,
+        },
+    )
+    .expect("Minting succeeds");
+
+    transfer(&mut chain, contract, ACC_ADMIN, ACC_ADMIN, ACC_HOLDER).expect("Transfer succeeds");
+
+    let update_operator = UpdateOperator {
+        update: OperatorUpdate::Add,
+        operator: Address::Account(ACC_ADMIN),
+    };
+    update(
+        &mut chain,
+        contract,
+        ACC_HOLDER,
+        "updateOperator",
+        &UpdateOperatorParams(vec![update_operator]),
+    )
+    .expect("Updating the operator succeeds");
+
+    (chain, module, contract)
+}
+
+/// Call an entrypoint of the contract as an account.
+fn update(
+    chain: &mut Chain,
+    contract: ContractAddress,
+    sender: AccountAddress,
+    entrypoint: &str,
+    parameter: &impl Serial,
+) -> Result<ContractInvokeSuccess, ContractInvokeError> {
+    chain.contract_update(
+        SIGNER,
+        sender,
+        Address::Account(sender),
+        ENERGY,
+        UpdateContractPayload {
+            amount: Amount::zero(),
+            address: contract,
+            receive_name: OwnedReceiveName::new_unchecked(format!("CIS2-Multi.{}", entrypoint)),
+            message: OwnedParameter::from_serial(parameter).expect("Parameter within size bounds"),
+        },
+    )
+}
+
+/// Call a view of the contract and parse the return value.
+fn view<R: Deserial>(
+    chain: &Chain,
+    contract: ContractAddress,
+    entrypoint: &str,
+    parameter: &impl Serial,
+) -> R {
+    chain
+        .contract_invoke(
+            ACC_ADMIN,
+            Address::Account(ACC_ADMIN),
+            ENERGY,
+            UpdateContractPayload {
+                amount: Amount::zero(),
+                address: contract,
+                receive_name: OwnedReceiveName::new_unchecked(format!("CIS2-Multi.{}", entrypoint)),
+                message: OwnedParameter::from_serial(parameter)
+                    .expect("Parameter within size bounds"),
+            },
+        )
+        .expect("Invoking the view succeeds")
+        .parse_return_value()
+        .expect("Valid return value")
+}
+
+/// Transfer 100 tokens from one account to another, sent by the given account.
+fn transfer(
+    chain: &mut Chain,
+    contract: ContractAddress,
+    sender: AccountAddress,
+    from: AccountAddress,
+    to: AccountAddress,
+) -> Result<ContractInvokeSuccess, ContractInvokeError> {
+    let transfer = Transfer {
+        token_id: new_token_id(1),
+        amount: TokenAmountU64(100),
+        from: Address::Account(from),
+        to: Receiver::from_account(to),
+        data: AdditionalData::empty(),
+    };
+    update(
+        chain,
+        contract,
+        sender,
+        "transfer",
+        &TransferParams::from(vec![transfer]),
+    )
+}
+
+/// Upgrade the contract to the given module as the given account, calling
+/// `migrate` of the new module.
+fn upgrade(
+    chain: &mut Chain,
+    contract: ContractAddress,
+    sender: AccountAddress,
+    module: ModuleReference,
+) -> Result<ContractInvokeSuccess, ContractInvokeError> {
+    update(
+        chain,
+        contract,
+        sender,
+        "upgrade",
+        &UpgradeParams {
+            module,
+            migrate: Some((
+                OwnedEntrypointName::new_unchecked("migrate".to_string()),
+                OwnedParameter::empty(),
+            )),
+        },
+    )
+}
+
+/// Query the balances of the admin and the holder.
+fn balances(chain: &Chain, contract: ContractAddress) -> Vec<TokenAmountU64> {
+    let response: BalanceOfQueryResponse<TokenAmountU64> = view(
+        chain,
+        contract,
+        "balanceOf",
+        &BalanceOfQueryParams {
+            queries: vec![
+                BalanceOfQuery {
+                    token_id: new_token_id(1),
+                    address: Address::Account(ACC_ADMIN),
+                },
+                BalanceOfQuery {
+                    token_id: new_token_id(1),
+                    address: Address::Account(ACC_HOLDER),
+                },
+            ],
+        },
+    );
+    response.0
+}
+
+/// Query whether the admin is an operator of the holder.
+fn is_admin_operator_of_holder(chain: &Chain, contract: ContractAddress) -> bool {
+    let response: OperatorOfQueryResponse = view(
+        chain,
+        contract,
+        "operatorOf",
+        &OperatorOfQueryParams {
+            queries: vec![OperatorOfQuery {
+                owner: Address::Account(ACC_HOLDER),
+                address: Address::Account(ACC_ADMIN),
+            }],
+        },
+    );
+    response.0[0]
+}
+
+/// Query the version of the state layout.
+fn state_version(chain: &Chain, contract: ContractAddress) -> u32 {
+    view(chain, contract, "stateVersion", &())
+}
+
+/// Test upgrading from the module of state version 1 migrates the state, such
+/// that the balances and operators are kept and can be used by the current
+/// module.
+#[test]
+#[ignore = "requires the built modules, see the README"]
+fn test_upgrade_from_v1() {
+    let (mut chain, module, contract) = initialize_v1_instance();
+    assert_eq!(
+        balances(&chain, contract),
+        [TokenAmountU64(300), TokenAmountU64(100)]
+    );
+
+    upgrade(&mut chain, contract, ACC_ADMIN, module).expect("Upgrading succeeds");
+
+    assert_eq!(state_version(&chain, contract), STATE_VERSION);
+    assert_eq!(
+        balances(&chain, contract),
+        [TokenAmountU64(300), TokenAmountU64(100)]
+    );
+    assert!(
+        is_admin_operator_of_holder(&chain, contract),
+        "The operator should be kept"
+    );
+
+    // The migrated operator can transfer the tokens of the holder.
+    transfer(&mut chain, contract, ACC_ADMIN, ACC_HOLDER, ACC_ADMIN)
+        .expect("Transfer by the operator succeeds");
+    assert_eq!(
+        balances(&chain, contract),
+        [TokenAmountU64(400), TokenAmountU64(0)]
+    );
+
+    // Migrating again leaves the migrated state as it is.
+    upgrade(&mut chain, contract, ACC_ADMIN, module).expect("Upgrading again succeeds");
+    assert_eq!(state_version(&chain, contract), STATE_VERSION);
+    assert_eq!(
+        balances(&chain, contract),
+        [TokenAmountU64(400), TokenAmountU64(0)]
+    );
+}
+
+/// Test only the admin can upgrade the contract.
+#[test]
+#[ignore = "requires the built modules, see the README"]
+fn test_upgrade_not_admin() {
+    let (mut chain, module, contract) = initialize_v1_instance();
+
+    let result = upgrade(&mut chain, contract, ACC_HOLDER, module);
+    assert!(result.is_err(), "Upgrading by a non-admin should fail");
+    assert_eq!(state_version(&chain, contract), 1);
+    assert_eq!(
+        balances(&chain, contract),
+        [TokenAmountU64(300), TokenAmountU64(100)]
+    );
+}